@@ -1,7 +1,9 @@
 use crate::dtype::u32::TensorU32;
+use crate::ops::activation::Activation;
 use crate::shape::compute_strides;
 use crate::shape::get_size;
 use crate::tensor::Tensor;
+use js_sys::Array;
 use js_sys::Float32Array;
 use js_sys::Float64Array;
 use js_sys::Int32Array;
@@ -17,6 +19,29 @@ pub struct TensorF64 {
 type Elem = f64;
 type Sel = TensorF64;
 
+impl TensorF64 {
+    pub fn from_tensor(tensor: Tensor<f64>) -> TensorF64 {
+        TensorF64 { tensor }
+    }
+
+    // Converts results accumulated in i64, as there is no 64 bit integer tensor.
+    // Exact as long as the magnitudes stay below 2^53
+    pub fn from_wide(wide: Tensor<i64>) -> TensorF64 {
+        let values = wide.get_values().iter().map(|&x| x as f64).collect();
+        TensorF64 {
+            tensor: Tensor::new_from_shape(wide.get_sh(), &values),
+        }
+    }
+
+    fn tensor_array(tensors: Vec<Tensor<Elem>>) -> Array {
+        let result = Array::new();
+        for tensor in tensors {
+            result.push(&JsValue::from(Self { tensor }));
+        }
+        result
+    }
+}
+
 #[wasm_bindgen]
 impl TensorF64 {
     pub fn create(shape: Uint32Array, values: Float64Array) -> TensorF64 {
@@ -344,6 +369,42 @@ impl TensorF64 {
         }
     }
 
+    pub fn sum_with_mode(&self, axes: Uint32Array, keep_dims: bool, mode: u32) -> TensorF64 {
+        TensorF64 {
+            tensor: self.tensor.sum_with_mode(axes, keep_dims, mode),
+        }
+    }
+
+    pub fn sum_square_with_mode(&self, axes: Uint32Array, keep_dims: bool, mode: u32) -> TensorF64 {
+        TensorF64 {
+            tensor: self.tensor.sum_square_with_mode(axes, keep_dims, mode),
+        }
+    }
+
+    pub fn reduce_mean_with_mode(
+        &self,
+        axes: Uint32Array,
+        keep_dims: bool,
+        mode: u32,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self.tensor.reduce_mean_with_mode(axes, keep_dims, mode),
+        }
+    }
+
+    pub fn reduce_mean_square_with_mode(
+        &self,
+        axes: Uint32Array,
+        keep_dims: bool,
+        mode: u32,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .reduce_mean_square_with_mode(axes, keep_dims, mode),
+        }
+    }
+
     pub fn reduce_log_sum(&self, axes: Uint32Array, keep_dims: bool) -> TensorF64 {
         TensorF64 {
             tensor: self.tensor.reduce_log_sum(axes, keep_dims),
@@ -356,6 +417,107 @@ impl TensorF64 {
         }
     }
 
+    pub fn sum_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .sum_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn sum_square_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .sum_square_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn reduce_mean_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .reduce_mean_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn max_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .max_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn min_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .min_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn product_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self
+                .tensor
+                .product_backward(&output.tensor, &grad.tensor, axes, keep_dims),
+        }
+    }
+
+    pub fn reduce_log_sum_exp_backward(
+        &self,
+        output: &TensorF64,
+        grad: &TensorF64,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self.tensor.reduce_log_sum_exp_backward(
+                &output.tensor,
+                &grad.tensor,
+                axes,
+                keep_dims,
+            ),
+        }
+    }
+
     pub fn conv(
         &self,
         kernel: &TensorF64,
@@ -424,7 +586,26 @@ impl TensorF64 {
         }
     }
 
-    // Mode: 0 == constant, 1 == reflect, 2 == edge
+    pub fn average_pool_with_mode(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+        mode: u32,
+    ) -> TensorF64 {
+        TensorF64 {
+            tensor: self.tensor.average_pool_with_mode(
+                kernel_shape,
+                pads,
+                strides,
+                include_pad,
+                mode,
+            ),
+        }
+    }
+
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
     pub fn pad(&self, pads: Uint32Array, mode: i32, value: f64) -> TensorF64 {
         TensorF64 {
             tensor: self.tensor.pad(pads, mode, value),
@@ -849,4 +1030,910 @@ impl TensorF64 {
             ),
         }
     }
+
+    pub fn segment_sum(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_sum(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_sum(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_sum(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_mean(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_mean(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_mean(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_mean(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_max(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_max(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_max(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_max(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_min(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_min(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_min(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_min(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    // Returns [values, indices, inverse_indices, counts] of the flattened tensor
+    pub fn unique(&self, sorted: bool) -> Array {
+        let (values, indices, inverse, counts) = self.tensor._unique(None, sorted);
+        TensorU32::with_indices(
+            JsValue::from(Self { tensor: values }),
+            vec![indices, inverse, counts],
+        )
+    }
+
+    pub fn unique_axis(&self, axis: u32, sorted: bool) -> Array {
+        let (values, indices, inverse, counts) = self.tensor._unique(Some(axis as usize), sorted);
+        TensorU32::with_indices(
+            JsValue::from(Self { tensor: values }),
+            vec![indices, inverse, counts],
+        )
+    }
+
+    pub fn histogram(&self, bins: u32) -> TensorU32 {
+        TensorU32::from_tensor(self.tensor.histogram(bins))
+    }
+
+    pub fn histogram_range(&self, bins: u32, min: Elem, max: Elem) -> TensorU32 {
+        TensorU32::from_tensor(self.tensor.histogram_range(bins, min, max))
+    }
+
+    pub fn layer_norm(&self, axis: u32, epsilon: Elem, scale: &Self, bias: &Self) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .layer_norm(axis as usize, epsilon, &scale.tensor, &bias.tensor),
+        }
+    }
+
+    // Returns [input_grad, scale_grad, bias_grad]
+    pub fn layer_norm_backward(
+        &self,
+        grad: &Self,
+        axis: u32,
+        epsilon: Elem,
+        scale: &Self,
+    ) -> Array {
+        let (input_grad, scale_grad, bias_grad) =
+            self.tensor
+                .layer_norm_backward(&grad.tensor, axis as usize, epsilon, &scale.tensor);
+        Self::tensor_array(vec![input_grad, scale_grad, bias_grad])
+    }
+
+    pub fn instance_norm(&self, epsilon: Elem, scale: &Self, bias: &Self) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .instance_norm(epsilon, &scale.tensor, &bias.tensor),
+        }
+    }
+
+    pub fn instance_norm_backward(&self, grad: &Self, epsilon: Elem, scale: &Self) -> Array {
+        let (input_grad, scale_grad, bias_grad) =
+            self.tensor
+                .instance_norm_backward(&grad.tensor, epsilon, &scale.tensor);
+        Self::tensor_array(vec![input_grad, scale_grad, bias_grad])
+    }
+
+    pub fn group_norm(&self, groups: u32, epsilon: Elem, scale: &Self, bias: &Self) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .group_norm(groups as usize, epsilon, &scale.tensor, &bias.tensor),
+        }
+    }
+
+    pub fn group_norm_backward(
+        &self,
+        grad: &Self,
+        groups: u32,
+        epsilon: Elem,
+        scale: &Self,
+    ) -> Array {
+        let (input_grad, scale_grad, bias_grad) =
+            self.tensor
+                .group_norm_backward(&grad.tensor, groups as usize, epsilon, &scale.tensor);
+        Self::tensor_array(vec![input_grad, scale_grad, bias_grad])
+    }
+
+    pub fn lp_normalize(&self, axis: u32, p: u32) -> Self {
+        Self {
+            tensor: self.tensor.lp_normalize(axis as usize, p),
+        }
+    }
+
+    pub fn lp_normalize_backward(&self, grad: &Self, axis: u32, p: u32) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .lp_normalize_backward(&grad.tensor, axis as usize, p),
+        }
+    }
+
+    // Returns [output, running_mean, running_var, saved_mean, saved_inv_std]
+    pub fn batch_norm_train(
+        &self,
+        scale: &Self,
+        bias: &Self,
+        running_mean: &Self,
+        running_var: &Self,
+        momentum: Elem,
+        epsilon: Elem,
+    ) -> Array {
+        let (output, new_mean, new_var, saved_mean, saved_inv_std) = self.tensor.batch_norm_train(
+            &scale.tensor,
+            &bias.tensor,
+            &running_mean.tensor,
+            &running_var.tensor,
+            momentum,
+            epsilon,
+        );
+        Self::tensor_array(vec![output, new_mean, new_var, saved_mean, saved_inv_std])
+    }
+
+    // Returns [input_grad, scale_grad, bias_grad]
+    pub fn batch_norm_backward(
+        &self,
+        grad: &Self,
+        scale: &Self,
+        saved_mean: &Self,
+        saved_inv_std: &Self,
+    ) -> Array {
+        let (input_grad, scale_grad, bias_grad) = self.tensor.batch_norm_backward(
+            &grad.tensor,
+            &scale.tensor,
+            &saved_mean.tensor,
+            &saved_inv_std.tensor,
+        );
+        Self::tensor_array(vec![input_grad, scale_grad, bias_grad])
+    }
+
+    pub fn max_pool(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> Self {
+        let (values, _) = self.tensor.max_pool(
+            kernel_shape,
+            pads,
+            strides,
+            dilations,
+            ceil_mode,
+            storage_order,
+        );
+        Self { tensor: values }
+    }
+
+    // Returns [values, indices], indices are flat positions in the input
+    pub fn max_pool_with_indices(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> Array {
+        let (values, indices) = self.tensor.max_pool(
+            kernel_shape,
+            pads,
+            strides,
+            dilations,
+            ceil_mode,
+            storage_order,
+        );
+        TensorU32::with_indices(JsValue::from(Self { tensor: values }), vec![indices])
+    }
+
+    pub fn global_average_pool(&self) -> Self {
+        Self {
+            tensor: self.tensor.global_average_pool(),
+        }
+    }
+
+    pub fn global_max_pool(&self) -> Self {
+        Self {
+            tensor: self.tensor.global_max_pool(),
+        }
+    }
+
+    pub fn lp_pool(
+        &self,
+        p: u32,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.lp_pool(p, kernel_shape, pads, strides),
+        }
+    }
+
+    pub fn global_lp_pool(&self, p: u32) -> Self {
+        Self {
+            tensor: self.tensor.global_lp_pool(p),
+        }
+    }
+
+    pub fn conv_backward_input(
+        &self,
+        kernel: &Self,
+        input_shape: Uint32Array,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_backward_input(
+                &kernel.tensor,
+                input_shape,
+                dilations,
+                group,
+                pads,
+                strides,
+            ),
+        }
+    }
+
+    pub fn conv_backward_kernel(
+        &self,
+        grad: &Self,
+        kernel_shape: Uint32Array,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_backward_kernel(
+                &grad.tensor,
+                kernel_shape,
+                dilations,
+                group,
+                pads,
+                strides,
+            ),
+        }
+    }
+
+    pub fn conv_backward_bias(&self) -> Self {
+        Self {
+            tensor: self.tensor.conv_backward_bias(),
+        }
+    }
+
+    pub fn average_pool_backward(
+        &self,
+        grad: &Self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.average_pool_backward(
+                &grad.tensor,
+                kernel_shape,
+                pads,
+                strides,
+                include_pad,
+            ),
+        }
+    }
+
+    pub fn max_pool_backward(
+        &self,
+        grad: &Self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.max_pool_backward(
+                &grad.tensor,
+                kernel_shape,
+                pads,
+                strides,
+                dilations,
+                ceil_mode,
+            ),
+        }
+    }
+
+    // Called on the gradient, indices are the ones returned by max_pool_with_indices
+    // with storage_order 0
+    pub fn max_pool_backward_indices(&self, indices: &TensorU32, input_shape: Uint32Array) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .max_pool_backward_indices(indices.get_tensor(), input_shape),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_auto_pad(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_auto_pad(
+                &kernel.tensor,
+                None,
+                dilations,
+                group,
+                strides,
+                auto_pad,
+                activation,
+            ),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_with_bias_auto_pad(
+        &self,
+        kernel: &Self,
+        bias: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_auto_pad(
+                &kernel.tensor,
+                Some(&bias.tensor),
+                dilations,
+                group,
+                strides,
+                auto_pad,
+                activation,
+            ),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn average_pool_auto_pad(
+        &self,
+        kernel_shape: Uint32Array,
+        strides: Uint32Array,
+        auto_pad: u32,
+        include_pad: bool,
+    ) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .average_pool_auto_pad(kernel_shape, strides, auto_pad, include_pad),
+        }
+    }
+
+    pub fn conv_transpose_output_padding(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_output_padding(
+                &kernel.tensor,
+                dilations,
+                group,
+                pads,
+                strides,
+                output_padding,
+            ),
+        }
+    }
+
+    // An empty output_shape derives the pads from the auto pad mode only
+    // Mode: 0 == NOTSET, 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_transpose_auto_pad(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+        output_shape: Uint32Array,
+        auto_pad: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_auto_pad(
+                &kernel.tensor,
+                dilations,
+                group,
+                strides,
+                output_padding,
+                output_shape,
+                auto_pad,
+            ),
+        }
+    }
+
+    // Activation: 0 == none, 1 == relu, 2 == relu6, 3 == leaky relu(alpha),
+    // 4 == clip(alpha = min, beta = max), 5 == sigmoid, 6 == hard sigmoid(alpha, beta),
+    // 7 == hard swish, 8 == tanh
+    pub fn conv_fused(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+        activation_alpha: f32,
+        activation_beta: f32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_fused(
+                &kernel.tensor,
+                None,
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::from_parameters(activation, activation_alpha, activation_beta),
+            ),
+        }
+    }
+
+    pub fn conv_with_bias_fused(
+        &self,
+        kernel: &Self,
+        bias: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+        activation_alpha: f32,
+        activation_beta: f32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_fused(
+                &kernel.tensor,
+                Some(&bias.tensor),
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::from_parameters(activation, activation_alpha, activation_beta),
+            ),
+        }
+    }
+
+    pub fn conv_transpose_fused(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+        activation_alpha: f32,
+        activation_beta: f32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_fused(
+                &kernel.tensor,
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::from_parameters(activation, activation_alpha, activation_beta),
+            ),
+        }
+    }
+
+    pub fn gemm_fused(
+        &self,
+        other: &Self,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: f64,
+        activation: u32,
+        activation_alpha: f32,
+        activation_beta: f32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.gemm_fused(
+                &other.tensor,
+                a_transpose,
+                b_transpose,
+                alpha,
+                None,
+                0.,
+                Activation::from_parameters(activation, activation_alpha, activation_beta),
+            ),
+        }
+    }
+
+    pub fn gemm_with_c_fused(
+        &self,
+        other: &Self,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: f64,
+        c: &Self,
+        beta: f64,
+        activation: u32,
+        activation_alpha: f32,
+        activation_beta: f32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.gemm_fused(
+                &other.tensor,
+                a_transpose,
+                b_transpose,
+                alpha,
+                Some(&c.tensor),
+                beta,
+                Activation::from_parameters(activation, activation_alpha, activation_beta),
+            ),
+        }
+    }
+
+    // Mode: 0 == nearest, 1 == linear, 2 == cubic
+    // Nearest mode: 0 == round_prefer_floor, 1 == round_prefer_ceil, 2 == floor, 3 == ceil
+    // Coordinate transform: 0 == half_pixel, 1 == align_corners, 2 == asymmetric,
+    // 3 == pytorch_half_pixel, 4 == tf_crop_and_resize
+    // Either sizes or scales has to be empty, roi is only used by tf_crop_and_resize
+    pub fn resize(
+        &self,
+        sizes: Uint32Array,
+        scales: Float32Array,
+        roi: Float32Array,
+        mode: u32,
+        coordinate_transform: u32,
+        nearest_mode: u32,
+        cubic_coeff_a: f32,
+        exclude_outside: bool,
+        extrapolation_value: f64,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.resize(
+                sizes,
+                scales,
+                roi,
+                mode,
+                coordinate_transform,
+                nearest_mode,
+                cubic_coeff_a,
+                exclude_outside,
+                extrapolation_value,
+            ),
+        }
+    }
+
+    // Pads in the layout [begins..., ends...] over axes, negative pads crop.
+    // An empty axes pads all axes
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
+    pub fn pad_axes(&self, pads: Int32Array, axes: Uint32Array, mode: i32, value: f64) -> Self {
+        Self {
+            tensor: self.tensor.pad_axes(pads, axes, mode, value),
+        }
+    }
+
+    // Channels last (NHWC) input of shape [N, D1, ..., Dn, C], the kernel keeps
+    // the shape [M, C/group, k1, ..., kn]
+    pub fn conv_channels_last(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_channels_last(
+                &kernel.tensor,
+                None,
+                dilations,
+                group,
+                pads,
+                strides,
+                activation,
+            ),
+        }
+    }
+
+    pub fn conv_with_bias_channels_last(
+        &self,
+        kernel: &Self,
+        bias: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_channels_last(
+                &kernel.tensor,
+                Some(&bias.tensor),
+                dilations,
+                group,
+                pads,
+                strides,
+                activation,
+            ),
+        }
+    }
+
+    pub fn conv_transpose_channels_last(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_channels_last(
+                &kernel.tensor,
+                dilations,
+                group,
+                pads,
+                strides,
+            ),
+        }
+    }
+
+    pub fn average_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.average_pool_channels_last(
+                kernel_shape,
+                pads,
+                strides,
+                include_pad,
+            ),
+        }
+    }
+
+    pub fn max_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Self {
+        let (values, _) =
+            self.tensor
+                .max_pool_channels_last(kernel_shape, pads, strides, dilations, ceil_mode);
+        Self { tensor: values }
+    }
+
+    // Returns [values, indices], indices are flat positions in the channels last input
+    pub fn max_pool_channels_last_with_indices(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Array {
+        let (values, indices) =
+            self.tensor
+                .max_pool_channels_last(kernel_shape, pads, strides, dilations, ceil_mode);
+        TensorU32::with_indices(JsValue::from(Self { tensor: values }), vec![indices])
+    }
+
+    // Mean, variance, scale and bias have one entry per channel
+    pub fn normalize_channels_last(
+        &self,
+        mean: &Self,
+        variance: &Self,
+        epsilon: f64,
+        scale: &Self,
+        bias: &Self,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.normalize_channels_last(
+                &mean.tensor,
+                &variance.tensor,
+                epsilon,
+                &scale.tensor,
+                &bias.tensor,
+            ),
+        }
+    }
+
+    // rois has shape [num_rois, 4] with (x1, y1, x2, y2), batch_indices has one entry per roi
+    // Mode: 0 == avg, 1 == max
+    pub fn roi_align(
+        &self,
+        rois: &Self,
+        batch_indices: &TensorU32,
+        output_height: u32,
+        output_width: u32,
+        sampling_ratio: u32,
+        spatial_scale: f64,
+        mode: u32,
+        half_pixel: bool,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.roi_align(
+                &rois.tensor,
+                batch_indices.get_tensor(),
+                output_height,
+                output_width,
+                sampling_ratio,
+                spatial_scale,
+                mode,
+                half_pixel,
+            ),
+        }
+    }
+
+    // rois has shape [num_rois, 5] with (batch_index, x1, y1, x2, y2)
+    pub fn max_roi_pool(
+        &self,
+        rois: &Self,
+        pooled_height: u32,
+        pooled_width: u32,
+        spatial_scale: f64,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.max_roi_pool(
+                &rois.tensor,
+                pooled_height,
+                pooled_width,
+                spatial_scale,
+            ),
+        }
+    }
+
+    // Called on boxes of shape [B, N, 4] with scores of shape [B, C, N], returns the
+    // selected indices of shape [num_selected, 3] as (batch, class, box)
+    // Center point box: 0 == (y1, x1, y2, x2), 1 == (x_center, y_center, width, height)
+    pub fn non_max_suppression(
+        &self,
+        scores: &Self,
+        max_output_boxes_per_class: u32,
+        iou_threshold: f32,
+        score_threshold: f32,
+        center_point_box: u32,
+    ) -> TensorU32 {
+        TensorU32::from_tensor(self.tensor.non_max_suppression(
+            &scores.tensor,
+            max_output_boxes_per_class,
+            iou_threshold,
+            score_threshold,
+            center_point_box,
+        ))
+    }
+
+    // Returns [selected_indices, scores] with the decayed score of every selected box
+    // Center point box: 0 == (y1, x1, y2, x2), 1 == (x_center, y_center, width, height)
+    // Method: 0 == linear, 1 == gaussian(sigma)
+    pub fn soft_non_max_suppression(
+        &self,
+        scores: &Self,
+        max_output_boxes_per_class: u32,
+        iou_threshold: f32,
+        score_threshold: f32,
+        center_point_box: u32,
+        method: u32,
+        sigma: f32,
+    ) -> Array {
+        let (indices, scores) = self.tensor.soft_non_max_suppression(
+            &scores.tensor,
+            max_output_boxes_per_class,
+            iou_threshold,
+            score_threshold,
+            center_point_box,
+            method,
+            sigma,
+        );
+        let result = Array::new();
+        result.push(&JsValue::from(TensorU32::from_tensor(indices)));
+        result.push(&JsValue::from(Self { tensor: scores }));
+        result
+    }
+
+    // Mode: 0 == DCR, 1 == CRD
+    pub fn depth_to_space(&self, blocksize: u32, mode: u32) -> Self {
+        Self {
+            tensor: self.tensor.depth_to_space(blocksize, mode),
+        }
+    }
+
+    pub fn space_to_depth(&self, blocksize: u32) -> Self {
+        Self {
+            tensor: self.tensor.space_to_depth(blocksize),
+        }
+    }
+
+    pub fn pixel_shuffle(&self, upscale_factor: u32) -> Self {
+        Self {
+            tensor: self.tensor.pixel_shuffle(upscale_factor),
+        }
+    }
+
+    pub fn pixel_unshuffle(&self, downscale_factor: u32) -> Self {
+        Self {
+            tensor: self.tensor.pixel_unshuffle(downscale_factor),
+        }
+    }
+
+    // Called on an input of shape [N, C, H, W] with a grid of shape [N, H_out, W_out, 2]
+    // Mode: 0 == bilinear, 1 == nearest, 2 == bicubic
+    // Padding mode: 0 == zeros, 1 == border, 2 == reflection
+    pub fn grid_sample(
+        &self,
+        grid: &Self,
+        mode: u32,
+        padding_mode: u32,
+        align_corners: bool,
+    ) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .grid_sample(&grid.tensor, mode, padding_mode, align_corners),
+        }
+    }
+
+    // Called on theta of shape [N, 2, 3], size is [N, C, H, W]
+    pub fn affine_grid(&self, size: Uint32Array, align_corners: bool) -> Self {
+        Self {
+            tensor: self.tensor.affine_grid(size, align_corners),
+        }
+    }
 }
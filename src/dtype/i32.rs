@@ -1,7 +1,9 @@
+use crate::dtype::f32::TensorF32;
 use crate::dtype::u32::TensorU32;
 use crate::shape::compute_strides;
 use crate::shape::get_size;
 use crate::tensor::Tensor;
+use js_sys::Array;
 use js_sys::Float32Array;
 use js_sys::Int32Array;
 use js_sys::Uint32Array;
@@ -16,6 +18,16 @@ pub struct TensorI32 {
 type Elem = i32;
 type Sel = TensorI32;
 
+impl TensorI32 {
+    pub fn get_tensor(&self) -> &Tensor<i32> {
+        &self.tensor
+    }
+
+    pub fn from_tensor(tensor: Tensor<i32>) -> TensorI32 {
+        TensorI32 { tensor }
+    }
+}
+
 #[wasm_bindgen]
 impl TensorI32 {
     pub fn create(shape: Uint32Array, values: Int32Array) -> TensorI32 {
@@ -279,7 +291,7 @@ impl TensorI32 {
         }
     }
 
-    // Mode: 0 == constant, 1 == reflect, 2 == edge
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
     pub fn pad(&self, pads: Uint32Array, mode: i32, value: i32) -> TensorI32 {
         TensorI32 {
             tensor: self.tensor.pad(pads, mode, value),
@@ -654,4 +666,427 @@ impl TensorI32 {
             ),
         }
     }
+
+    pub fn segment_sum(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_sum(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_sum(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_sum(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_mean(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_mean(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_mean(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_mean(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_max(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_max(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_max(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_max(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    pub fn segment_min(&self, segment_ids: &TensorU32) -> Self {
+        Self {
+            tensor: self.tensor.segment_min(segment_ids.get_tensor()),
+        }
+    }
+
+    pub fn unsorted_segment_min(&self, segment_ids: &TensorU32, num_segments: usize) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .unsorted_segment_min(segment_ids.get_tensor(), num_segments),
+        }
+    }
+
+    // Returns [values, indices, inverse_indices, counts] of the flattened tensor
+    pub fn unique(&self, sorted: bool) -> Array {
+        let (values, indices, inverse, counts) = self.tensor._unique(None, sorted);
+        TensorU32::with_indices(
+            JsValue::from(Self { tensor: values }),
+            vec![indices, inverse, counts],
+        )
+    }
+
+    pub fn unique_axis(&self, axis: u32, sorted: bool) -> Array {
+        let (values, indices, inverse, counts) = self.tensor._unique(Some(axis as usize), sorted);
+        TensorU32::with_indices(
+            JsValue::from(Self { tensor: values }),
+            vec![indices, inverse, counts],
+        )
+    }
+
+    pub fn bincount(&self, minlength: u32) -> TensorU32 {
+        TensorU32::from_tensor(self.tensor._bincount(None, minlength as usize))
+    }
+
+    pub fn bincount_weighted(&self, weights: &TensorF32, minlength: u32) -> TensorF32 {
+        TensorF32::from_tensor(
+            self.tensor
+                ._bincount(Some(weights.get_tensor()), minlength as usize),
+        )
+    }
+
+    pub fn max_pool(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> Self {
+        let (values, _) = self.tensor.max_pool(
+            kernel_shape,
+            pads,
+            strides,
+            dilations,
+            ceil_mode,
+            storage_order,
+        );
+        Self { tensor: values }
+    }
+
+    // Returns [values, indices], indices are flat positions in the input
+    pub fn max_pool_with_indices(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> Array {
+        let (values, indices) = self.tensor.max_pool(
+            kernel_shape,
+            pads,
+            strides,
+            dilations,
+            ceil_mode,
+            storage_order,
+        );
+        TensorU32::with_indices(JsValue::from(Self { tensor: values }), vec![indices])
+    }
+
+    pub fn global_average_pool(&self) -> Self {
+        Self {
+            tensor: self.tensor.global_average_pool(),
+        }
+    }
+
+    pub fn global_max_pool(&self) -> Self {
+        Self {
+            tensor: self.tensor.global_max_pool(),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_auto_pad(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_auto_pad(
+                &kernel.tensor,
+                None,
+                dilations,
+                group,
+                strides,
+                auto_pad,
+                activation,
+            ),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_with_bias_auto_pad(
+        &self,
+        kernel: &Self,
+        bias: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_auto_pad(
+                &kernel.tensor,
+                Some(&bias.tensor),
+                dilations,
+                group,
+                strides,
+                auto_pad,
+                activation,
+            ),
+        }
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn average_pool_auto_pad(
+        &self,
+        kernel_shape: Uint32Array,
+        strides: Uint32Array,
+        auto_pad: u32,
+        include_pad: bool,
+    ) -> Self {
+        Self {
+            tensor: self
+                .tensor
+                .average_pool_auto_pad(kernel_shape, strides, auto_pad, include_pad),
+        }
+    }
+
+    pub fn conv_transpose_output_padding(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_output_padding(
+                &kernel.tensor,
+                dilations,
+                group,
+                pads,
+                strides,
+                output_padding,
+            ),
+        }
+    }
+
+    // An empty output_shape derives the pads from the auto pad mode only
+    // Mode: 0 == NOTSET, 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_transpose_auto_pad(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+        output_shape: Uint32Array,
+        auto_pad: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_auto_pad(
+                &kernel.tensor,
+                dilations,
+                group,
+                strides,
+                output_padding,
+                output_shape,
+                auto_pad,
+            ),
+        }
+    }
+
+    // Mode: 0 == nearest, 1 == linear, 2 == cubic
+    // Nearest mode: 0 == round_prefer_floor, 1 == round_prefer_ceil, 2 == floor, 3 == ceil
+    // Coordinate transform: 0 == half_pixel, 1 == align_corners, 2 == asymmetric,
+    // 3 == pytorch_half_pixel, 4 == tf_crop_and_resize
+    // Either sizes or scales has to be empty, roi is only used by tf_crop_and_resize
+    pub fn resize(
+        &self,
+        sizes: Uint32Array,
+        scales: Float32Array,
+        roi: Float32Array,
+        mode: u32,
+        coordinate_transform: u32,
+        nearest_mode: u32,
+        cubic_coeff_a: f32,
+        exclude_outside: bool,
+        extrapolation_value: i32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.resize(
+                sizes,
+                scales,
+                roi,
+                mode,
+                coordinate_transform,
+                nearest_mode,
+                cubic_coeff_a,
+                exclude_outside,
+                extrapolation_value,
+            ),
+        }
+    }
+
+    // Pads in the layout [begins..., ends...] over axes, negative pads crop.
+    // An empty axes pads all axes
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
+    pub fn pad_axes(&self, pads: Int32Array, axes: Uint32Array, mode: i32, value: i32) -> Self {
+        Self {
+            tensor: self.tensor.pad_axes(pads, axes, mode, value),
+        }
+    }
+
+    // Channels last (NHWC) input of shape [N, D1, ..., Dn, C], the kernel keeps
+    // the shape [M, C/group, k1, ..., kn]
+    pub fn conv_channels_last(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_channels_last(
+                &kernel.tensor,
+                None,
+                dilations,
+                group,
+                pads,
+                strides,
+                activation,
+            ),
+        }
+    }
+
+    pub fn conv_with_bias_channels_last(
+        &self,
+        kernel: &Self,
+        bias: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_channels_last(
+                &kernel.tensor,
+                Some(&bias.tensor),
+                dilations,
+                group,
+                pads,
+                strides,
+                activation,
+            ),
+        }
+    }
+
+    pub fn conv_transpose_channels_last(
+        &self,
+        kernel: &Self,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.conv_transpose_channels_last(
+                &kernel.tensor,
+                dilations,
+                group,
+                pads,
+                strides,
+            ),
+        }
+    }
+
+    pub fn average_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Self {
+        Self {
+            tensor: self.tensor.average_pool_channels_last(
+                kernel_shape,
+                pads,
+                strides,
+                include_pad,
+            ),
+        }
+    }
+
+    pub fn max_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Self {
+        let (values, _) =
+            self.tensor
+                .max_pool_channels_last(kernel_shape, pads, strides, dilations, ceil_mode);
+        Self { tensor: values }
+    }
+
+    // Returns [values, indices], indices are flat positions in the channels last input
+    pub fn max_pool_channels_last_with_indices(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Array {
+        let (values, indices) =
+            self.tensor
+                .max_pool_channels_last(kernel_shape, pads, strides, dilations, ceil_mode);
+        TensorU32::with_indices(JsValue::from(Self { tensor: values }), vec![indices])
+    }
+
+    // Mode: 0 == DCR, 1 == CRD
+    pub fn depth_to_space(&self, blocksize: u32, mode: u32) -> Self {
+        Self {
+            tensor: self.tensor.depth_to_space(blocksize, mode),
+        }
+    }
+
+    pub fn space_to_depth(&self, blocksize: u32) -> Self {
+        Self {
+            tensor: self.tensor.space_to_depth(blocksize),
+        }
+    }
+
+    pub fn pixel_shuffle(&self, upscale_factor: u32) -> Self {
+        Self {
+            tensor: self.tensor.pixel_shuffle(upscale_factor),
+        }
+    }
+
+    pub fn pixel_unshuffle(&self, downscale_factor: u32) -> Self {
+        Self {
+            tensor: self.tensor.pixel_unshuffle(downscale_factor),
+        }
+    }
 }
@@ -15,12 +15,25 @@ pub mod dtype {
 }
 
 pub mod ops {
+    pub mod accumulate;
+    pub mod activation;
     pub mod aggregate;
     pub mod binary;
+    pub mod channels_last;
     pub mod conv;
+    pub mod image;
     pub mod matmul;
+    pub mod nms;
+    pub mod normalization;
+    pub mod quantized;
+    pub mod resize;
+    pub mod roi;
+    pub mod segment;
+    pub mod spatial;
+    pub mod statistics;
     pub mod unary;
     pub mod util;
+    pub mod winograd;
     pub mod sparse {
         pub mod aggregate;
         pub mod binary;
@@ -32,14 +45,27 @@ pub mod ops {
 pub mod tests {
     pub mod shape;
     pub mod tensor {
+        pub mod activation;
         pub mod aggregate;
         pub mod basic;
 
         #[cfg(feature = "benchmark")]
         pub mod benchmark;
+        pub mod channels_last;
         pub mod conv;
+        pub mod depth_to_space;
+        pub mod gradient;
+        pub mod image;
+        pub mod nms;
+        pub mod normalization;
         pub mod pool;
+        pub mod quantized;
+        pub mod resize;
+        pub mod roi;
+        pub mod segment;
+        pub mod spatial;
         pub mod sparse;
+        pub mod statistics;
         pub mod tensor;
     }
 }
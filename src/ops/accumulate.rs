@@ -0,0 +1,172 @@
+use crate::tensor::*;
+use num_traits::one;
+use num_traits::zero;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::ToPrimitive;
+
+// Below this many elements pairwise summation falls back to a plain loop
+const PAIRWISE_BLOCK_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SumMode {
+    Sequential,
+    Pairwise,
+    Kahan,
+}
+
+impl SumMode {
+    // Mode: 0 == sequential, 1 == pairwise, 2 == kahan
+    pub fn from_u32(mode: u32) -> SumMode {
+        match mode {
+            0 => SumMode::Sequential,
+            1 => SumMode::Pairwise,
+            2 => SumMode::Kahan,
+            _ => panic!("Unknown summation mode {}", mode),
+        }
+    }
+}
+
+pub fn sequential_sum<DType>(values: &[DType]) -> DType
+where
+    DType: Copy,
+    DType: Num,
+{
+    let mut result = zero();
+    for i in 0..values.len() {
+        result = result + values[i];
+    }
+    result
+}
+
+pub fn pairwise_sum<DType>(values: &[DType]) -> DType
+where
+    DType: Copy,
+    DType: Num,
+{
+    if values.len() <= PAIRWISE_BLOCK_SIZE {
+        return sequential_sum(values);
+    }
+
+    let mid = values.len() / 2;
+    pairwise_sum(&values[..mid]) + pairwise_sum(&values[mid..])
+}
+
+pub fn kahan_sum<DType>(values: &[DType]) -> DType
+where
+    DType: Copy,
+    DType: Num,
+{
+    let mut result = zero();
+    let mut compensation = zero();
+    for i in 0..values.len() {
+        let y = values[i] - compensation;
+        let t = result + y;
+        compensation = (t - result) - y;
+        result = t;
+    }
+    result
+}
+
+pub fn accumulate<DType>(values: &[DType], mode: SumMode) -> DType
+where
+    DType: Copy,
+    DType: Num,
+{
+    match mode {
+        SumMode::Sequential => sequential_sum(values),
+        SumMode::Pairwise => pairwise_sum(values),
+        SumMode::Kahan => kahan_sum(values),
+    }
+}
+
+// Integer division rounding half away from zero, count has to be positive
+pub fn divide_rounded<DType>(value: DType, count: DType) -> DType
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+{
+    let quotient = value / count;
+    let remainder = value % count;
+    if remainder >= zero() {
+        if remainder + remainder >= count {
+            return quotient + one();
+        }
+    } else if zero::<DType>() - (remainder + remainder) >= count {
+        return quotient - one();
+    }
+    quotient
+}
+
+// Element types whose sums are accumulated in a wider type
+pub trait Widen: Copy {
+    type Wide: Copy + Num + PartialOrd + FromPrimitive + ToPrimitive;
+
+    fn widen(self) -> Self::Wide;
+
+    // Converts back, saturating at the bounds of the narrow type
+    fn narrow(value: Self::Wide) -> Self;
+}
+
+macro_rules! impl_widen {
+    ($narrow:ty, $wide:ty) => {
+        impl Widen for $narrow {
+            type Wide = $wide;
+
+            fn widen(self) -> $wide {
+                self as $wide
+            }
+
+            fn narrow(value: $wide) -> $narrow {
+                if value > <$narrow>::MAX as $wide {
+                    <$narrow>::MAX
+                } else if value < <$narrow>::MIN as $wide {
+                    <$narrow>::MIN
+                } else {
+                    value as $narrow
+                }
+            }
+        }
+    };
+}
+
+// Sums of squares exceed i32 after about 33000 values of 8 bits and
+// a single square of a 16 bit value, so everything accumulates in i64
+impl_widen!(u8, i64);
+impl_widen!(i8, i64);
+impl_widen!(u16, i64);
+impl_widen!(i16, i64);
+
+impl<DType> Tensor<DType>
+where
+    DType: Widen,
+{
+    pub fn _widen(&self) -> Tensor<DType::Wide> {
+        let mut values = vec![zero(); self.size];
+        for i in 0..self.size {
+            values[i] = self.get_ix(i).widen();
+        }
+
+        Tensor::new(
+            self.get_sh().to_vec(),
+            self.get_strides().to_vec(),
+            self.size,
+            values,
+        )
+    }
+
+    pub fn _narrow(wide: &Tensor<DType::Wide>) -> Tensor<DType> {
+        let mut values = Vec::with_capacity(wide.size);
+        for i in 0..wide.size {
+            values.push(DType::narrow(wide.get_ix(i)));
+        }
+
+        Tensor::new(
+            wide.get_sh().to_vec(),
+            wide.get_strides().to_vec(),
+            wide.size,
+            values,
+        )
+    }
+}
@@ -0,0 +1,114 @@
+use num_traits::zero;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::ToPrimitive;
+
+// Activation fused into convolutions and gemm
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activation {
+    None,
+    Relu,
+    Relu6,
+    LeakyRelu(f32),
+    Clip(f32, f32),
+    Sigmoid,
+    HardSigmoid(f32, f32),
+    HardSwish,
+    Tanh,
+}
+
+impl Activation {
+    // Activation: 0 == none, 1 == relu, 2 == relu6
+    pub fn from_u32(activation: u32) -> Activation {
+        match activation {
+            0 => Activation::None,
+            1 => Activation::Relu,
+            2 => Activation::Relu6,
+            _ => panic!("Activation {} requires parameters", activation),
+        }
+    }
+
+    // Activation: 0 == none, 1 == relu, 2 == relu6, 3 == leaky relu(alpha),
+    // 4 == clip(alpha = min, beta = max), 5 == sigmoid, 6 == hard sigmoid(alpha, beta),
+    // 7 == hard swish, 8 == tanh
+    pub fn from_parameters(activation: u32, alpha: f32, beta: f32) -> Activation {
+        match activation {
+            0 => Activation::None,
+            1 => Activation::Relu,
+            2 => Activation::Relu6,
+            3 => Activation::LeakyRelu(alpha),
+            4 => Activation::Clip(alpha, beta),
+            5 => Activation::Sigmoid,
+            6 => Activation::HardSigmoid(alpha, beta),
+            7 => Activation::HardSwish,
+            8 => Activation::Tanh,
+            _ => panic!("Unknown activation {}", activation),
+        }
+    }
+
+    // Applies the activation to every value. Relu and relu6 are computed in DType,
+    // all other activations in f64
+    pub fn apply<DType>(&self, values: &mut [DType])
+    where
+        DType: Copy,
+        DType: Num,
+        DType: FromPrimitive,
+        DType: ToPrimitive,
+        DType: PartialOrd,
+    {
+        match *self {
+            Activation::None => {}
+            Activation::Relu => {
+                for i in 0..values.len() {
+                    if values[i] < zero() {
+                        values[i] = zero();
+                    }
+                }
+            }
+            Activation::Relu6 => {
+                let six = match DType::from_u32(6) {
+                    Some(six) => six,
+                    None => {
+                        panic!("Encountered dtype that can not represent 6 for relu6 activation")
+                    }
+                };
+                for i in 0..values.len() {
+                    if values[i] < zero() {
+                        values[i] = zero();
+                    } else if values[i] > six {
+                        values[i] = six;
+                    }
+                }
+            }
+            Activation::LeakyRelu(alpha) => {
+                map_f64(values, |x| if x < 0.0 { x * alpha as f64 } else { x })
+            }
+            Activation::Clip(min, max) => map_f64(values, |x| x.max(min as f64).min(max as f64)),
+            Activation::Sigmoid => map_f64(values, |x| 1.0 / (1.0 + (-x).exp())),
+            Activation::HardSigmoid(alpha, beta) => map_f64(values, |x| {
+                (alpha as f64 * x + beta as f64).max(0.0).min(1.0)
+            }),
+            Activation::HardSwish => map_f64(values, |x| x * (x / 6.0 + 0.5).max(0.0).min(1.0)),
+            Activation::Tanh => map_f64(values, |x| x.tanh()),
+        }
+    }
+}
+
+#[inline]
+fn map_f64<DType, F>(values: &mut [DType], f: F)
+where
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    F: Fn(f64) -> f64,
+{
+    for i in 0..values.len() {
+        let x = match values[i].to_f64() {
+            Some(x) => x,
+            None => panic!("Can not convert value to f64 for activation"),
+        };
+        values[i] = match DType::from_f64(f(x)) {
+            Some(y) => y,
+            None => panic!("Activation result can not be represented by dtype"),
+        };
+    }
+}
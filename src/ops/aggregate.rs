@@ -1,6 +1,8 @@
+use crate::ops::accumulate::*;
 use crate::shape::*;
 use crate::tensor::*;
 use js_sys::Uint32Array;
+use num_traits::one;
 use num_traits::zero;
 use num_traits::Float;
 use num_traits::FromPrimitive;
@@ -13,54 +15,39 @@ where
     DType: PartialOrd,
     DType: FromPrimitive,
 {
-    #[inline]
-    pub fn pool_continuous<F, F2, F3>(
-        &self,
-        axes: &Vec<usize>,
-        keep_dims: bool,
-        op: F,
-        postprocess: bool,
-        post: F2,
-        init: bool,
-        init_func: F3,
-    ) -> Tensor<DType>
-    where
-        F: Fn(DType, DType) -> DType,
-        F2: Fn(DType) -> DType,
-        F3: Fn(DType) -> DType,
-    {
-        let mut result_rank = self.rank() - axes.len() as usize;
-
-        if keep_dims {
-            result_rank = self.rank()
-        }
-
-        let mut result_shape = vec![0; result_rank];
+    // Shape of the reduction of axes, its size and the number of input
+    // elements reduced into every output element
+    fn reduced_shape(&self, axes: &Vec<usize>, keep_dims: bool) -> (Vec<usize>, usize, usize) {
+        let mut result_shape = Vec::with_capacity(self.rank());
         let mut result_size = 1;
         let mut sum_size = 1;
         let mut axes_ix = 0;
-        let mut result_ix = 0;
         for i in 0..self.rank() {
             if axes_ix < axes.len() && axes[axes_ix] as usize == i {
                 axes_ix += 1;
                 sum_size *= self.get_dim_size(i);
                 if keep_dims {
-                    result_shape[result_ix] = 1;
-                    result_ix += 1;
+                    result_shape.push(1);
                 }
             } else {
-                result_shape[result_ix] = self.get_dim_size(i);
-                result_ix += 1;
+                result_shape.push(self.get_dim_size(i));
                 result_size *= self.get_dim_size(i);
             }
         }
-        let result_strides = compute_strides(&result_shape);
-        let mut values = vec![zero(); result_size];
+        (result_shape, result_size, sum_size)
+    }
 
+    // Calls visit(output_ix, input_ix, step_size) for every output element of a
+    // reduction over continuous axes. The reduced input elements are at
+    // input_ix + k * step_size
+    #[inline]
+    fn visit_continuous_reduction<F>(&self, axes: &Vec<usize>, mut visit: F)
+    where
+        F: FnMut(usize, usize, usize),
+    {
         let self_strides = compute_strides_no_zero(self.get_sh());
 
         let step_size = self_strides[axes[axes.len() - 1]];
-        let cont_size = step_size;
 
         let mut input_ix_step_size = if axes[0] > 0 {
             self_strides[axes[0] - 1]
@@ -72,26 +59,49 @@ where
         }
         let num_input_steps = self.size / input_ix_step_size;
 
-        let mut input_start_ix;
-        let mut output_ix;
         for i in 0..num_input_steps {
-            input_start_ix = i * input_ix_step_size;
-            output_ix = i * cont_size;
+            let input_start_ix = i * input_ix_step_size;
+            let output_ix = i * step_size;
 
-            for j in 0..cont_size {
-                let mut res = self.get_ix(input_start_ix + j);
-                if init {
-                    res = init_func(res);
-                }
-                for k in 1..sum_size {
-                    res = op(self.get_ix(input_start_ix + j + k * step_size), res);
-                }
-                if postprocess {
-                    res = post(res);
-                }
-                values[output_ix + j] = res;
+            for j in 0..step_size {
+                visit(output_ix + j, input_start_ix + j, step_size);
             }
         }
+    }
+
+    #[inline]
+    pub fn pool_continuous<F, F2, F3>(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        op: F,
+        postprocess: bool,
+        post: F2,
+        init: bool,
+        init_func: F3,
+    ) -> Tensor<DType>
+    where
+        F: Fn(DType, DType) -> DType,
+        F2: Fn(DType) -> DType,
+        F3: Fn(DType) -> DType,
+    {
+        let (result_shape, result_size, sum_size) = self.reduced_shape(axes, keep_dims);
+        let result_strides = compute_strides(&result_shape);
+        let mut values = vec![zero(); result_size];
+
+        self.visit_continuous_reduction(axes, |output_ix, input_ix, step_size| {
+            let mut res = self.get_ix(input_ix);
+            if init {
+                res = init_func(res);
+            }
+            for k in 1..sum_size {
+                res = op(self.get_ix(input_ix + k * step_size), res);
+            }
+            if postprocess {
+                res = post(res);
+            }
+            values[output_ix] = res;
+        });
 
         Tensor::new(result_shape, result_strides, result_size, values)
     }
@@ -309,6 +319,339 @@ where
             None => panic!("Cant convert from usize to given dtype"),
         }
     }
+
+    // Maps every input position to the position of the output it is reduced into
+    pub fn reduced_positions(&self, axes: &Vec<usize>) -> Vec<usize> {
+        let mut reduced_shape = self.get_sh().to_vec();
+        for i in 0..axes.len() {
+            reduced_shape[axes[i]] = 1;
+        }
+        let reduced_strides = compute_strides(&reduced_shape);
+
+        let mut positions = vec![0; self.size];
+        let mut index = vec![0; self.rank()];
+        for i in 0..self.size {
+            positions[i] = index_to_pos(&index, &reduced_strides);
+            increment_index(&mut index, self.get_sh());
+        }
+        positions
+    }
+
+    #[inline]
+    pub fn _reduce_backward<F>(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        op: F,
+    ) -> Tensor<DType>
+    where
+        F: Fn(DType, DType, DType, usize) -> DType,
+    {
+        let expected_rank = if keep_dims {
+            self.rank()
+        } else if self.rank() > axes.len() {
+            self.rank() - axes.len()
+        } else {
+            1
+        };
+        if grad.rank() != expected_rank || grad.size != output.size {
+            panic!("Gradient shape does not match the reduction output");
+        }
+
+        let positions = self.reduced_positions(axes);
+
+        let mut values = vec![zero(); self.size];
+        for i in 0..self.size {
+            let pos = positions[i];
+            values[i] = op(self.get_ix(i), output.get_ix(pos), grad.get_ix(pos), pos);
+        }
+
+        Tensor::new(
+            self.get_sh().to_vec(),
+            compute_strides(self.get_sh()),
+            self.size,
+            values,
+        )
+    }
+
+    pub fn _sum_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        self._reduce_backward(output, grad, axes, keep_dims, |_x, _y, g, _pos| g)
+    }
+
+    pub fn _sum_square_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        self._reduce_backward(output, grad, axes, keep_dims, |x, _y, g, _pos| (x + x) * g)
+    }
+
+    pub fn _reduce_mean_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut pool_size = 1;
+        for i in 0..axes.len() {
+            pool_size *= self.get_dim_size(axes[i]);
+        }
+
+        match DType::from_usize(pool_size) {
+            Some(s) => {
+                self._reduce_backward(output, grad, axes, keep_dims, |_x, _y, g, _pos| g / s)
+            }
+            None => panic!("Tensor size too large to compute mean for given dtype"),
+        }
+    }
+
+    // The gradient is split evenly between all inputs equal to the extremum
+    pub fn _extremum_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let positions = self.reduced_positions(axes);
+
+        let mut ties = vec![0; output.size];
+        for i in 0..self.size {
+            if self.get_ix(i) == output.get_ix(positions[i]) {
+                ties[positions[i]] += 1;
+            }
+        }
+
+        self._reduce_backward(output, grad, axes, keep_dims, |x, y, g, pos| {
+            if x != y {
+                return zero();
+            }
+            match DType::from_usize(ties[pos]) {
+                Some(count) => g / count,
+                None => panic!("Can not represent number of ties for given dtype"),
+            }
+        })
+    }
+
+    pub fn _max_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        self._extremum_backward(output, grad, axes, keep_dims)
+    }
+
+    pub fn _min_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        self._extremum_backward(output, grad, axes, keep_dims)
+    }
+
+    // Uses the product of all other inputs instead of output / x,
+    // so inputs that are zero get the correct gradient
+    pub fn _product_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let positions = self.reduced_positions(axes);
+
+        let mut non_zero_product = vec![one(); output.size];
+        let mut zeros = vec![0; output.size];
+        for i in 0..self.size {
+            let x = self.get_ix(i);
+            if x == zero() {
+                zeros[positions[i]] += 1;
+            } else {
+                non_zero_product[positions[i]] = non_zero_product[positions[i]] * x;
+            }
+        }
+
+        self._reduce_backward(output, grad, axes, keep_dims, |x, _y, g, pos| {
+            if zeros[pos] == 0 {
+                g * (non_zero_product[pos] / x)
+            } else if zeros[pos] == 1 && x == zero() {
+                g * non_zero_product[pos]
+            } else {
+                zero()
+            }
+        })
+    }
+
+    pub fn _pool_with_mode<F, F2>(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        mode: SumMode,
+        map: F,
+        post: F2,
+    ) -> Tensor<DType>
+    where
+        F: Fn(DType) -> DType,
+        F2: Fn(DType) -> DType,
+    {
+        let (mut result_shape, result_size, sum_size) = self.reduced_shape(axes, keep_dims);
+        if result_shape.len() == 0 {
+            result_shape = vec![1];
+        }
+
+        if !self.axes_continuous(axes) {
+            // Move the reduced axes to the back, so they form one continuous block
+            let mut permutation: Vec<usize> =
+                (0..self.rank()).filter(|i| !axes.contains(i)).collect();
+            let kept = permutation.len();
+            permutation.extend(axes.iter());
+            let moved_axes: Vec<usize> = (kept..self.rank()).collect();
+
+            return self
+                ._transpose(&permutation)
+                ._pool_with_mode(&moved_axes, false, mode, map, post)
+                ._reshape(&result_shape);
+        }
+
+        let result_strides = compute_strides(&result_shape);
+        let mut values = vec![zero(); result_size];
+
+        let mut buffer = vec![zero(); sum_size];
+        self.visit_continuous_reduction(axes, |output_ix, input_ix, step_size| {
+            for k in 0..sum_size {
+                buffer[k] = map(self.get_ix(input_ix + k * step_size));
+            }
+            values[output_ix] = post(accumulate(&buffer, mode));
+        });
+
+        Tensor::new(result_shape, result_strides, result_size, values)
+    }
+
+    pub fn _sum_with_mode(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        mode: SumMode,
+    ) -> Tensor<DType> {
+        self._pool_with_mode(axes, keep_dims, mode, |x: DType| x, |x: DType| x)
+    }
+
+    pub fn _sum_square_with_mode(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        mode: SumMode,
+    ) -> Tensor<DType> {
+        self._pool_with_mode(axes, keep_dims, mode, |x: DType| x * x, |x: DType| x)
+    }
+
+    pub fn _reduce_mean_with_mode(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        mode: SumMode,
+    ) -> Tensor<DType> {
+        let mut pool_size = 1;
+        for i in 0..axes.len() {
+            pool_size *= self.get_dim_size(axes[i]);
+        }
+
+        match DType::from_usize(pool_size) {
+            Some(s) => self._pool_with_mode(axes, keep_dims, mode, |x: DType| x, |x: DType| x / s),
+            None => panic!("Tensor size too large to compute mean for given dtype"),
+        }
+    }
+
+    pub fn _reduce_mean_square_with_mode(
+        &self,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+        mode: SumMode,
+    ) -> Tensor<DType> {
+        let mut pool_size = 1;
+        for i in 0..axes.len() {
+            pool_size *= self.get_dim_size(axes[i]);
+        }
+
+        match DType::from_usize(pool_size) {
+            Some(s) => {
+                self._pool_with_mode(axes, keep_dims, mode, |x: DType| x * x, |x: DType| x / s)
+            }
+            None => panic!("Cant convert from usize to given dtype"),
+        }
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: FromPrimitive,
+    DType: Widen,
+{
+    pub fn _sum_wide(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType::Wide> {
+        self._widen()._sum(axes, keep_dims)
+    }
+
+    pub fn _sum_square_wide(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType::Wide> {
+        self._widen()._sum_square(axes, keep_dims)
+    }
+
+    pub fn _sum_widened(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType> {
+        Tensor::_narrow(&self._sum_wide(axes, keep_dims))
+    }
+
+    pub fn _sum_square_widened(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType> {
+        Tensor::_narrow(&self._sum_square_wide(axes, keep_dims))
+    }
+
+    pub fn _reduce_mean_widened(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType> {
+        self.mean_widened(&self._sum_wide(axes, keep_dims), axes)
+    }
+
+    pub fn _reduce_mean_square_widened(&self, axes: &Vec<usize>, keep_dims: bool) -> Tensor<DType> {
+        self.mean_widened(&self._sum_square_wide(axes, keep_dims), axes)
+    }
+
+    fn mean_widened(&self, sums: &Tensor<DType::Wide>, axes: &Vec<usize>) -> Tensor<DType> {
+        let mut pool_size = 1;
+        for i in 0..axes.len() {
+            pool_size *= self.get_dim_size(axes[i]);
+        }
+
+        match DType::Wide::from_usize(pool_size) {
+            Some(s) => {
+                let mut values = Vec::with_capacity(sums.size);
+                for i in 0..sums.size {
+                    values.push(DType::narrow(divide_rounded(sums.get_ix(i), s)));
+                }
+
+                Tensor::new(
+                    sums.get_sh().to_vec(),
+                    sums.get_strides().to_vec(),
+                    sums.size,
+                    values,
+                )
+            }
+            None => panic!("Tensor size too large to compute mean for given dtype"),
+        }
+    }
 }
 
 impl<DType> Tensor<DType>
@@ -342,6 +685,18 @@ where
             |x: DType| x.exp(),
         );
     }
+
+    pub fn _reduce_log_sum_exp_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: &Vec<usize>,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        self._reduce_backward(output, grad, axes, keep_dims, |x, y, g, _pos| {
+            g * (x - y).exp()
+        })
+    }
 }
 
 impl<DType> Tensor<DType>
@@ -406,6 +761,195 @@ where
         }
         return self._reduce_mean_square(&ax, keep_dims);
     }
+
+    // Mode: 0 == sequential, 1 == pairwise, 2 == kahan
+    pub fn sum_with_mode(&self, axes: Uint32Array, keep_dims: bool, mode: u32) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_with_mode(&ax, keep_dims, SumMode::from_u32(mode));
+    }
+
+    pub fn sum_square_with_mode(
+        &self,
+        axes: Uint32Array,
+        keep_dims: bool,
+        mode: u32,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_square_with_mode(&ax, keep_dims, SumMode::from_u32(mode));
+    }
+
+    pub fn reduce_mean_with_mode(
+        &self,
+        axes: Uint32Array,
+        keep_dims: bool,
+        mode: u32,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_mean_with_mode(&ax, keep_dims, SumMode::from_u32(mode));
+    }
+
+    pub fn reduce_mean_square_with_mode(
+        &self,
+        axes: Uint32Array,
+        keep_dims: bool,
+        mode: u32,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_mean_square_with_mode(&ax, keep_dims, SumMode::from_u32(mode));
+    }
+
+    pub fn sum_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_backward(output, grad, &ax, keep_dims);
+    }
+
+    pub fn sum_square_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_square_backward(output, grad, &ax, keep_dims);
+    }
+
+    pub fn reduce_mean_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_mean_backward(output, grad, &ax, keep_dims);
+    }
+
+    pub fn max_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._max_backward(output, grad, &ax, keep_dims);
+    }
+
+    pub fn min_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._min_backward(output, grad, &ax, keep_dims);
+    }
+
+    pub fn product_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._product_backward(output, grad, &ax, keep_dims);
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: FromPrimitive,
+    DType: Widen,
+{
+    pub fn sum_wide(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType::Wide> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_wide(&ax, keep_dims);
+    }
+
+    pub fn sum_square_wide(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType::Wide> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_square_wide(&ax, keep_dims);
+    }
+
+    pub fn sum_widened(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_widened(&ax, keep_dims);
+    }
+
+    pub fn sum_square_widened(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._sum_square_widened(&ax, keep_dims);
+    }
+
+    pub fn reduce_mean_widened(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_mean_widened(&ax, keep_dims);
+    }
+
+    pub fn reduce_mean_square_widened(&self, axes: Uint32Array, keep_dims: bool) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_mean_square_widened(&ax, keep_dims);
+    }
 }
 
 impl<DType> Tensor<DType>
@@ -431,4 +975,18 @@ where
         }
         return self._reduce_log_sum_exp(&ax, keep_dims);
     }
+
+    pub fn reduce_log_sum_exp_backward(
+        &self,
+        output: &Tensor<DType>,
+        grad: &Tensor<DType>,
+        axes: Uint32Array,
+        keep_dims: bool,
+    ) -> Tensor<DType> {
+        let mut ax: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            ax[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._reduce_log_sum_exp_backward(output, grad, &ax, keep_dims);
+    }
 }
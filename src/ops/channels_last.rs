@@ -0,0 +1,624 @@
+use crate::ops::accumulate::*;
+use crate::ops::activation::*;
+use crate::ops::conv::*;
+use crate::shape::*;
+use crate::tensor::*;
+use crate::utils::conv_output_size;
+use crate::utils::conv_transpose_output_size;
+use crate::utils::pool_output_size;
+use js_sys::Uint32Array;
+use num_traits::zero;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::ToPrimitive;
+
+// Kernels for channels last (NHWC) data. Inputs and outputs have the shape
+// [N, D1, ..., Dn, C], kernels keep the channels first layout [M, C/group, k1, ..., kn]
+
+// Shape [N, C, D1, ..., Dn] of a channels last shape, as expected by the output size helpers
+fn channels_first_shape(shape: &Vec<usize>) -> Vec<usize> {
+    let rank = shape.len();
+    let mut result = vec![shape[0], shape[rank - 1]];
+    result.extend_from_slice(&shape[1..rank - 1]);
+    result
+}
+
+// Channels last shape [N, D1, ..., Dn, C]
+fn channels_last_shape(batches: usize, spatial_shape: &Vec<usize>, channels: usize) -> Vec<usize> {
+    let mut result = vec![batches];
+    result.extend_from_slice(spatial_shape);
+    result.push(channels);
+    result
+}
+
+// Flat spatial input position of every output and kernel position pair of a
+// strided, dilated and padded window, None for positions in the padding
+fn window_positions(
+    input_shape: &Vec<usize>,
+    output_shape: &Vec<usize>,
+    kernel_shape: &Vec<usize>,
+    dilations: &Vec<usize>,
+    pads: &Vec<usize>,
+    strides: &Vec<usize>,
+) -> Vec<Option<usize>> {
+    let data_rank = input_shape.len();
+    let output_size = get_size(output_shape);
+    let kernel_size = get_size(kernel_shape);
+    let input_strides = compute_strides_no_zero(input_shape);
+
+    let mut output_indices = vec![0; data_rank];
+    let mut kernel_indices = vec![0; data_rank];
+    let mut positions = vec![None; output_size * kernel_size];
+    for o_ix in 0..output_size {
+        for kernel_ix in 0..kernel_size {
+            let mut position = 0;
+            let mut inside = true;
+            for axis in 0..data_rank {
+                let ix =
+                    output_indices[axis] * strides[axis] + kernel_indices[axis] * dilations[axis];
+                if ix < pads[axis] || ix - pads[axis] >= input_shape[axis] {
+                    inside = false;
+                    break;
+                }
+                position += (ix - pads[axis]) * input_strides[axis];
+            }
+            if inside {
+                positions[o_ix * kernel_size + kernel_ix] = Some(position);
+            }
+            increment_index(&mut kernel_indices, kernel_shape);
+        }
+        increment_index(&mut output_indices, output_shape);
+    }
+    positions
+}
+
+// Like window_positions for a transposed convolution, where every output position
+// gathers the inputs the kernel scatters to it
+fn transpose_positions(
+    input_shape: &Vec<usize>,
+    output_shape: &Vec<usize>,
+    kernel_shape: &Vec<usize>,
+    dilations: &Vec<usize>,
+    pads: &Vec<usize>,
+    strides: &Vec<usize>,
+) -> Vec<Option<usize>> {
+    let data_rank = input_shape.len();
+    let output_size = get_size(output_shape);
+    let kernel_size = get_size(kernel_shape);
+    let input_strides = compute_strides_no_zero(input_shape);
+
+    let mut output_indices = vec![0; data_rank];
+    let mut kernel_indices = vec![0; data_rank];
+    let mut positions = vec![None; output_size * kernel_size];
+    for o_ix in 0..output_size {
+        for kernel_ix in 0..kernel_size {
+            let mut position = 0;
+            let mut inside = true;
+            for axis in 0..data_rank {
+                let trans_kernel_ix = kernel_shape[axis] - kernel_indices[axis] - 1;
+                let ix = output_indices[axis] as i32 - pads[axis] as i32
+                    + (trans_kernel_ix * dilations[axis]) as i32;
+                if ix % strides[axis] as i32 != 0 {
+                    inside = false;
+                    break;
+                }
+                let ix = ix / strides[axis] as i32;
+                if ix < 0 || ix >= input_shape[axis] as i32 {
+                    inside = false;
+                    break;
+                }
+                position += ix as usize * input_strides[axis];
+            }
+            if inside {
+                positions[o_ix * kernel_size + kernel_ix] = Some(position);
+            }
+            increment_index(&mut kernel_indices, kernel_shape);
+        }
+        increment_index(&mut output_indices, output_shape);
+    }
+    positions
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+{
+    // Sums the products of kernel and input over the given input positions,
+    // accumulating in the same order as the channels first kernels
+    fn _gather_channels_last(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        positions: &Vec<Option<usize>>,
+        output_spatial_shape: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let rank = self.rank();
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(rank - 1);
+        let M = kernel.get_dim_size(0);
+        let CG = kernel.get_dim_size(1);
+
+        let kernel_size = get_size_from(kernel.get_sh(), 2);
+        let spatial_size = get_size(&self.get_sh()[1..rank - 1].to_vec());
+        let output_size = get_size(output_spatial_shape);
+
+        let output_shape = channels_last_shape(N, output_spatial_shape, M);
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        for n in 0..N {
+            for o_ix in 0..output_size {
+                let output_base = (n * output_size + o_ix) * M;
+                let window = &positions[o_ix * kernel_size..(o_ix + 1) * kernel_size];
+
+                for m in 0..M {
+                    let mut result = match bias {
+                        None => zero(),
+                        Some(b) => b.get_ix(m),
+                    };
+
+                    for cg in 0..CG {
+                        let c = (m * CG + cg) % C;
+                        let kernel_base = (m * CG + cg) * kernel_size;
+
+                        for kernel_ix in 0..kernel_size {
+                            if let Some(position) = window[kernel_ix] {
+                                let w_i = kernel.get_ix(kernel_base + kernel_ix);
+                                let x_i = self.get_ix((n * spatial_size + position) * C + c);
+                                result = result + w_i * x_i;
+                            }
+                        }
+                    }
+
+                    values[output_base + m] = result;
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    pub fn _conv_channels_last(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: &Vec<usize>,
+        _group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let rank = self.rank();
+        let input_spatial_shape = self.get_sh()[1..rank - 1].to_vec();
+        let kernel_spatial_shape = kernel.get_sh()[2..].to_vec();
+        let R = conv_output_size(
+            &channels_first_shape(self.get_sh()),
+            kernel.get_sh(),
+            pads,
+            dilations,
+            strides,
+            2,
+        );
+
+        let positions = window_positions(
+            &input_spatial_shape,
+            &R,
+            &kernel_spatial_shape,
+            dilations,
+            pads,
+            strides,
+        );
+        let result = self._gather_channels_last(kernel, bias, &positions, &R);
+
+        let mut values = result.get_values().clone();
+        activation.apply(&mut values);
+        Tensor::new_from_shape(result.get_sh(), &values)
+    }
+
+    pub fn _conv_transpose_channels_last(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: &Vec<usize>,
+        _group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let rank = self.rank();
+        let input_spatial_shape = self.get_sh()[1..rank - 1].to_vec();
+        let kernel_spatial_shape = kernel.get_sh()[2..].to_vec();
+        let R = conv_transpose_output_size(
+            &channels_first_shape(self.get_sh()),
+            kernel.get_sh(),
+            pads,
+            dilations,
+            strides,
+            2,
+        );
+
+        let positions = transpose_positions(
+            &input_spatial_shape,
+            &R,
+            &kernel_spatial_shape,
+            dilations,
+            pads,
+            strides,
+        );
+        self._gather_channels_last(kernel, None, &positions, &R)
+    }
+
+    // Reduces the values of every pooling window of every channel. The count passed
+    // to reduce includes padded positions if include_pad is set
+    #[inline]
+    pub fn _window_pool_channels_last<F>(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+        reduce: F,
+    ) -> Tensor<DType>
+    where
+        F: Fn(&[DType], u32) -> DType,
+    {
+        let rank = self.rank();
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(rank - 1);
+        let data_rank = rank - 2;
+        let input_spatial_shape = self.get_sh()[1..rank - 1].to_vec();
+        let spatial_size = get_size(&input_spatial_shape);
+        let kernel_size = get_size(kernel_shape);
+
+        let dilations = vec![1; data_rank];
+        let R = conv_output_size(
+            &channels_first_shape(self.get_sh()),
+            kernel_shape,
+            pads,
+            &dilations,
+            strides,
+            0,
+        );
+        let output_size = get_size(&R);
+        let positions = window_positions(
+            &input_spatial_shape,
+            &R,
+            kernel_shape,
+            &dilations,
+            pads,
+            strides,
+        );
+
+        let output_shape = channels_last_shape(N, &R, C);
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        let mut window = Vec::with_capacity(kernel_size);
+
+        for n in 0..N {
+            for o_ix in 0..output_size {
+                let output_base = (n * output_size + o_ix) * C;
+                let positions = &positions[o_ix * kernel_size..(o_ix + 1) * kernel_size];
+                let count = if include_pad {
+                    kernel_size
+                } else {
+                    positions.iter().filter(|p| p.is_some()).count()
+                };
+
+                for c in 0..C {
+                    window.clear();
+                    for position in positions.iter() {
+                        if let Some(position) = position {
+                            window.push(self.get_ix((n * spatial_size + position) * C + c));
+                        }
+                    }
+
+                    values[output_base + c] = reduce(&window, count as u32);
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    pub fn _average_pool_channels_last(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        self._window_pool_channels_last(
+            kernel_shape,
+            pads,
+            strides,
+            include_pad,
+            |window: &[DType], count: u32| match DType::from_u32(count) {
+                Some(co) => accumulate(window, SumMode::Sequential) / co,
+                None => panic!("DType can not represent kernel size in average pool"),
+            },
+        )
+    }
+
+    // Returns the maximum of every window and its flat index into the channels last
+    // input, padded positions are never selected
+    pub fn _max_pool_channels_last(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        dilations: &Vec<usize>,
+        ceil_mode: bool,
+    ) -> (Tensor<DType>, Tensor<u32>) {
+        let rank = self.rank();
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(rank - 1);
+        let input_spatial_shape = self.get_sh()[1..rank - 1].to_vec();
+        let spatial_size = get_size(&input_spatial_shape);
+        let kernel_size = get_size(kernel_shape);
+
+        let R = pool_output_size(
+            &channels_first_shape(self.get_sh()),
+            kernel_shape,
+            pads,
+            dilations,
+            strides,
+            ceil_mode,
+        );
+        let output_size = get_size(&R);
+        let positions = window_positions(
+            &input_spatial_shape,
+            &R,
+            kernel_shape,
+            dilations,
+            pads,
+            strides,
+        );
+
+        let output_shape = channels_last_shape(N, &R, C);
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+        let mut indices = vec![0; o_size];
+
+        for n in 0..N {
+            for o_ix in 0..output_size {
+                let output_base = (n * output_size + o_ix) * C;
+                let positions = &positions[o_ix * kernel_size..(o_ix + 1) * kernel_size];
+
+                for c in 0..C {
+                    let mut found = false;
+                    let mut result = zero();
+                    let mut result_ix = 0;
+                    for position in positions.iter() {
+                        if let Some(position) = position {
+                            let ix = (n * spatial_size + position) * C + c;
+                            let x = self.get_ix(ix);
+                            if !found || x > result {
+                                found = true;
+                                result = x;
+                                result_ix = ix;
+                            }
+                        }
+                    }
+
+                    values[output_base + c] = result;
+                    indices[output_base + c] = result_ix as u32;
+                }
+            }
+        }
+
+        (
+            Tensor::new(output_shape.clone(), output_strides.clone(), o_size, values),
+            Tensor::new(output_shape, output_strides, o_size, indices),
+        )
+    }
+
+    pub fn conv_channels_last(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_channels_last(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    pub fn conv_transpose_channels_last(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_transpose_channels_last(
+            kernel,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+        );
+    }
+
+    pub fn average_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        return self._average_pool_channels_last(&_kernel_shape, &_pads, &_strides, include_pad);
+    }
+
+    pub fn max_pool_channels_last(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> (Tensor<DType>, Tensor<u32>) {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        let mut _dilations: Vec<usize> = vec![0; dilations.length() as usize];
+        for i in 0..dilations.length() {
+            _dilations[i as usize] = dilations.get_index(i) as usize;
+        }
+
+        return self._max_pool_channels_last(
+            &_kernel_shape,
+            &_pads,
+            &_strides,
+            &_dilations,
+            ceil_mode,
+        );
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+    DType: Widen,
+{
+    pub fn _conv_channels_last_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let wide_bias = match bias {
+            None => None,
+            Some(b) => Some(b._widen()),
+        };
+
+        Tensor::_narrow(&self._widen()._conv_channels_last(
+            &kernel._widen(),
+            wide_bias.as_ref(),
+            dilations,
+            group,
+            pads,
+            strides,
+            activation,
+        ))
+    }
+
+    pub fn _average_pool_channels_last_widened(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let sums = self._widen()._window_pool_channels_last(
+            kernel_shape,
+            pads,
+            strides,
+            include_pad,
+            |window: &[DType::Wide], count: u32| match DType::Wide::from_u32(count) {
+                Some(co) => divide_rounded(sequential_sum(window), co),
+                None => panic!("DType can not represent kernel size in average pool"),
+            },
+        );
+        Tensor::_narrow(&sums)
+    }
+
+    pub fn conv_channels_last_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_channels_last_widened(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    pub fn average_pool_channels_last_widened(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        return self._average_pool_channels_last_widened(
+            &_kernel_shape,
+            &_pads,
+            &_strides,
+            include_pad,
+        );
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: Float,
+{
+    // Batch normalization of channels last data with mean, variance, scale and bias of size C
+    pub fn normalize_channels_last(
+        &self,
+        mean: &Tensor<DType>,
+        variance: &Tensor<DType>,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+    ) -> Tensor<DType> {
+        let C = self.get_dim_size(self.rank() - 1);
+        let size = get_size(self.get_sh());
+
+        let mut deviations = vec![zero(); C];
+        for c in 0..C {
+            deviations[c] = (variance.get_ix(c) + epsilon).sqrt();
+        }
+
+        let mut values = vec![zero(); size];
+        for i in 0..size {
+            let c = i % C;
+            let res = (self.get_ix(i) - mean.get_ix(c)) / deviations[c];
+            values[i] = res * scale.get_ix(c) + bias.get_ix(c);
+        }
+
+        Tensor::new_from_shape(self.get_sh(), &values)
+    }
+}
@@ -1,19 +1,36 @@
+use crate::ops::accumulate::*;
+use crate::ops::activation::*;
 use crate::shape::*;
 use crate::tensor::*;
+use crate::utils::conv_auto_pads;
 use crate::utils::conv_output_size;
 use crate::utils::conv_transpose_output_size;
+use crate::utils::conv_transpose_pads;
+use crate::utils::pool_auto_pads;
+use crate::utils::pool_output_size;
+use crate::utils::usize_vec;
+use crate::utils::AutoPad;
+use crate::utils::PadMode;
 use js_sys::Float32Array;
+use js_sys::Int32Array;
 use js_sys::Uint32Array;
+use num_traits::one;
 use num_traits::zero;
 use num_traits::Float;
 use num_traits::FromPrimitive;
 use num_traits::Num;
+use num_traits::ToPrimitive;
+
+// Minimum number of multiplications per output channel and batch for which
+// _conv lowers to a matrix multiplication
+const IM2COL_THRESHOLD: usize = 4096;
 
 impl<DType> Tensor<DType>
 where
     DType: Copy,
     DType: Num,
     DType: FromPrimitive,
+    DType: ToPrimitive,
     DType: PartialOrd,
 {
     pub fn _conv(
@@ -24,7 +41,32 @@ where
         group: usize,
         _pads: &Vec<usize>,
         _strides: &Vec<usize>,
-        activation: u32,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let W = kernel.get_sh();
+        let R = conv_output_size(self.get_sh(), W, _pads, _dilations, _strides, 2);
+        let work = get_size(&R) * get_size_from(W, 1);
+
+        if self.rank() == 4 && W[1] == 1 && W[0] == self.get_dim_size(1) {
+            self._conv_depthwise(kernel, bias, _dilations, _pads, _strides, activation)
+        } else if get_size_from(W, 2) == 1 {
+            self._conv_pointwise(kernel, bias, _pads, _strides, activation)
+        } else if work >= IM2COL_THRESHOLD {
+            self._conv_im2col(kernel, bias, _dilations, group, _pads, _strides, activation)
+        } else {
+            self._conv_direct(kernel, bias, _dilations, group, _pads, _strides, activation)
+        }
+    }
+
+    pub fn _conv_direct(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        _dilations: &Vec<usize>,
+        group: usize,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        activation: Activation,
     ) -> Tensor<DType> {
         let N = self.get_dim_size(0);
         let C = self.get_dim_size(1);
@@ -118,26 +160,385 @@ where
                     }
                 }
 
-                if activation != 0 {
-                    for o in 0..output_size {
-                        let mut result = values[basis + o];
-                        if activation == 1 {
-                            if result < zero() {
-                                result = zero()
+                activation.apply(&mut values[basis..basis + output_size]);
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // Transposed convolution with output_padding added to the end of every spatial axis
+    pub fn _conv_transpose_output_padding(
+        &self,
+        kernel: &Tensor<DType>,
+        _dilations: &Vec<usize>,
+        group: usize,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        output_padding: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let data_rank = _strides.len();
+        let mut pads = _pads.clone();
+        for axis in 0..data_rank {
+            pads[axis + data_rank] += output_padding[axis];
+        }
+        self._conv_transpose(kernel, _dilations, group, &pads, _strides)
+    }
+
+    // Transposed convolution with pads as defined by ONNX ConvTranspose, derived
+    // from output_shape or the auto pad mode. Negative pads extend the output by zeros
+    pub fn _conv_transpose_auto_pad(
+        &self,
+        kernel: &Tensor<DType>,
+        _dilations: &Vec<usize>,
+        group: usize,
+        _strides: &Vec<usize>,
+        output_padding: &Vec<usize>,
+        output_shape: Option<&Vec<usize>>,
+        auto_pad: AutoPad,
+    ) -> Tensor<DType> {
+        let data_rank = _strides.len();
+        let onnx_pads = conv_transpose_pads(
+            self.get_sh(),
+            kernel.get_sh(),
+            _dilations,
+            _strides,
+            output_padding,
+            output_shape,
+            auto_pad,
+            2,
+        );
+
+        // _conv_transpose pads the input by dkernel - 1 - pad, negative values
+        // are applied by cropping the result
+        let mut pads = vec![0; 2 * data_rank];
+        let mut crop_begin = vec![0; data_rank];
+        let mut crop_end = vec![0; data_rank];
+        let mut crop = false;
+        for axis in 0..data_rank {
+            let dkernel = (_dilations[axis] * (kernel.get_dim_size(axis + 2) - 1) + 1) as i64;
+            let begin = dkernel - 1 - onnx_pads[axis];
+            let end = dkernel - 1 - onnx_pads[axis + data_rank] + output_padding[axis] as i64;
+
+            pads[axis] = begin.max(0) as usize;
+            pads[axis + data_rank] = end.max(0) as usize;
+            crop_begin[axis] = (-begin).max(0) as usize;
+            crop_end[axis] = (-end).max(0) as usize;
+            crop = crop || begin < 0 || end < 0;
+        }
+
+        let result = self._conv_transpose(kernel, _dilations, group, &pads, _strides);
+        if !crop {
+            return result;
+        }
+
+        let mut ends = vec![0; data_rank];
+        for axis in 0..data_rank {
+            ends[axis] = result.get_dim_size(axis + 2) - crop_end[axis];
+        }
+        let axes: Vec<usize> = (2..data_rank + 2).collect();
+        result._slice(&crop_begin, &ends, &axes, &vec![1; data_rank])
+    }
+
+    // Lowers the convolution to one matrix multiplication per batch and input
+    // channel offset. The columns hold the input values of every window, in the
+    // same order in which _conv_direct accumulates them, and the bias is
+    // prepended as a row of ones so both paths produce identical results
+    pub fn _conv_im2col(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let D = self.get_sh();
+        let W = kernel.get_sh();
+        let M = kernel.get_dim_size(0);
+        let CG = kernel.get_dim_size(1);
+        if CG * group != C {
+            panic!(
+                "Kernel with {} channels does not match {} input channels in {} groups",
+                CG, C, group
+            );
+        }
+
+        let kernel_size = get_size_from(W, 2);
+
+        let data_rank = self.rank() - 2;
+
+        let R = conv_output_size(D, W, pads, dilations, strides, 2);
+        let output_size = get_size(&R);
+
+        let mut output_shape = vec![0; data_rank + 2];
+        output_shape[0] = N;
+        output_shape[1] = M;
+        for i in 0..data_rank {
+            output_shape[i + 2] = R[i];
+        }
+
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        // Offsets into the padded input of every output and kernel position
+        let mut output_offsets = vec![0; output_size * data_rank];
+        let mut output_indices = vec![0; data_rank];
+        for o_ix in 0..output_size {
+            for axis in 0..data_rank {
+                output_offsets[o_ix * data_rank + axis] = output_indices[axis] * strides[axis];
+            }
+            increment_index(&mut output_indices, &R);
+        }
+        let mut kernel_offsets = vec![0; kernel_size * data_rank];
+        let mut kernel_indices = vec![0; data_rank];
+        for kernel_ix in 0..kernel_size {
+            for axis in 0..data_rank {
+                kernel_offsets[kernel_ix * data_rank + axis] =
+                    kernel_indices[axis] * dilations[axis];
+            }
+            increment_index(&mut kernel_indices, &W[2..].to_vec());
+        }
+        let input_strides = compute_strides_no_zero(D);
+
+        let bias_rows = if bias.is_some() { 1 } else { 0 };
+        let rows = bias_rows + CG * kernel_size;
+
+        // Output channels reading the same input channels share one column matrix
+        let mut channel_offsets: Vec<usize> = (0..M).map(|m| (m * CG) % C).collect();
+        channel_offsets.sort();
+        channel_offsets.dedup();
+
+        let mut columns = vec![zero(); rows * output_size];
+
+        for &channel_offset in &channel_offsets {
+            let filters: Vec<usize> = (0..M).filter(|m| (m * CG) % C == channel_offset).collect();
+
+            let mut weights = vec![zero(); filters.len() * rows];
+            for (f, &m) in filters.iter().enumerate() {
+                if let Some(bi) = bias {
+                    weights[f * rows] = bi.get_ix(m);
+                }
+                for cg in 0..CG {
+                    let kernel_base = m * kernel.get_strides_at(0) + cg * kernel.get_strides_at(1);
+                    for kernel_ix in 0..kernel_size {
+                        weights[f * rows + bias_rows + cg * kernel_size + kernel_ix] =
+                            kernel.get_ix(kernel_base + kernel_ix);
+                    }
+                }
+            }
+            let weights = Tensor::new_from_shape(&vec![filters.len(), rows], &weights);
+
+            for n in 0..N {
+                if bias_rows == 1 {
+                    for o_ix in 0..output_size {
+                        columns[o_ix] = one();
+                    }
+                }
+
+                for cg in 0..CG {
+                    let c = (channel_offset + cg) % C;
+                    let input_base = n * input_strides[0] + c * input_strides[1];
+
+                    for kernel_ix in 0..kernel_size {
+                        let row = bias_rows + cg * kernel_size + kernel_ix;
+
+                        for o_ix in 0..output_size {
+                            let mut position = input_base;
+                            let mut skip = false;
+                            for axis in 0..data_rank {
+                                let ix = output_offsets[o_ix * data_rank + axis]
+                                    + kernel_offsets[kernel_ix * data_rank + axis];
+
+                                if ix < pads[axis] || ix - pads[axis] >= D[axis + 2] {
+                                    skip = true;
+                                    break;
+                                }
+
+                                position += (ix - pads[axis]) * input_strides[axis + 2];
+                            }
+
+                            columns[row * output_size + o_ix] =
+                                if skip { zero() } else { self.get_ix(position) };
+                        }
+                    }
+                }
+
+                let column_matrix = Tensor::new_from_shape(&vec![rows, output_size], &columns);
+                let result = weights._gemm(&column_matrix, false, false, one(), None, one());
+
+                for (f, &m) in filters.iter().enumerate() {
+                    let basis = output_strides[0] * n + output_strides[1] * m;
+                    for o_ix in 0..output_size {
+                        values[basis + o_ix] = result.get_ix(f * output_size + o_ix);
+                    }
+                    activation.apply(&mut values[basis..basis + output_size]);
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // 2D convolution where every output channel reads exactly one input
+    // channel, i.e. group == C == M
+    pub fn _conv_depthwise(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        _dilations: &Vec<usize>,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        let KH = kernel.get_dim_size(2);
+        let KW = kernel.get_dim_size(3);
+
+        let R = conv_output_size(
+            self.get_sh(),
+            kernel.get_sh(),
+            _pads,
+            _dilations,
+            _strides,
+            2,
+        );
+        let output_size = R[0] * R[1];
+
+        let output_shape = vec![N, C, R[0], R[1]];
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        for n in 0..N {
+            for c in 0..C {
+                let basis = (n * C + c) * output_size;
+                let input_base = (n * C + c) * H * W;
+                let kernel_base = c * KH * KW;
+                let b = match bias {
+                    Some(bi) => bi.get_ix(c),
+                    None => zero(),
+                };
+
+                for oy in 0..R[0] {
+                    for ox in 0..R[1] {
+                        let mut result = b;
+                        for ky in 0..KH {
+                            let y =
+                                (oy * _strides[0] + ky * _dilations[0]) as i32 - _pads[0] as i32;
+                            if y < 0 || y >= H as i32 {
+                                continue;
                             }
-                        } else if activation == 2 {
-                            match DType::from_u32(6) {
-                                Some(six) => if result < zero() {
-                                    result = zero()
-                                } else if result > six {
-                                    result = six
-                                },
-                                None => panic!("Encountered dtype that can not represent 6 for relu6 activation")
+                            let row = input_base + y as usize * W;
+
+                            for kx in 0..KW {
+                                let x = (ox * _strides[1] + kx * _dilations[1]) as i32
+                                    - _pads[1] as i32;
+                                if x < 0 || x >= W as i32 {
+                                    continue;
+                                }
+
+                                result = result
+                                    + kernel.get_ix(kernel_base + ky * KW + kx)
+                                        * self.get_ix(row + x as usize);
                             }
                         }
-                        values[basis + o] = result;
+                        values[basis + oy * R[1] + ox] = result;
+                    }
+                }
+
+                activation.apply(&mut values[basis..basis + output_size]);
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // Convolution with a kernel of spatial size 1 in every dimension
+    pub fn _conv_pointwise(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let D = self.get_sh();
+        let M = kernel.get_dim_size(0);
+        let CG = kernel.get_dim_size(1);
+
+        let data_rank = self.rank() - 2;
+        let spatial_size = get_size_from(D, 2);
+
+        let R = conv_output_size(D, kernel.get_sh(), _pads, &vec![1; data_rank], _strides, 2);
+        let output_size = get_size(&R);
+
+        let mut output_shape = vec![0; data_rank + 2];
+        output_shape[0] = N;
+        output_shape[1] = M;
+        for i in 0..data_rank {
+            output_shape[i + 2] = R[i];
+        }
+
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        // Spatial input position of every output position, None if it lies in the padding
+        let input_strides = compute_strides_no_zero(D);
+        let mut positions = vec![None; output_size];
+        let mut output_indices = vec![0; data_rank];
+        for o_ix in 0..output_size {
+            let mut position = 0;
+            let mut inside = true;
+            for axis in 0..data_rank {
+                let ix = output_indices[axis] * _strides[axis];
+                if ix < _pads[axis] || ix - _pads[axis] >= D[axis + 2] {
+                    inside = false;
+                    break;
+                }
+                position += (ix - _pads[axis]) * input_strides[axis + 2];
+            }
+            if inside {
+                positions[o_ix] = Some(position);
+            }
+            increment_index(&mut output_indices, &R);
+        }
+
+        for n in 0..N {
+            for m in 0..M {
+                let basis = output_strides[0] * n + output_strides[1] * m;
+
+                if let Some(bi) = bias {
+                    let b = bi.get_ix(m);
+                    for o_ix in 0..output_size {
+                        values[basis + o_ix] = b;
+                    }
+                }
+
+                for cg in 0..CG {
+                    let c = (m * CG + cg) % C;
+                    let w = kernel.get_ix(m * CG + cg);
+                    let input_base = (n * C + c) * spatial_size;
+
+                    for o_ix in 0..output_size {
+                        if let Some(position) = positions[o_ix] {
+                            values[basis + o_ix] =
+                                values[basis + o_ix] + w * self.get_ix(input_base + position);
+                        }
                     }
                 }
+
+                activation.apply(&mut values[basis..basis + output_size]);
             }
         }
 
@@ -257,6 +658,49 @@ where
         strides: &Vec<usize>,
         include_pad: bool,
     ) -> Tensor<DType> {
+        self._average_pool_with_mode(
+            kernel_shape,
+            pads,
+            strides,
+            include_pad,
+            SumMode::Sequential,
+        )
+    }
+
+    pub fn _average_pool_with_mode(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+        mode: SumMode,
+    ) -> Tensor<DType> {
+        self._window_pool(
+            kernel_shape,
+            pads,
+            strides,
+            include_pad,
+            |window: &[DType], count: u32| match DType::from_u32(count) {
+                Some(co) => accumulate(window, mode) / co,
+                None => panic!("DType can not represent kernel size in average pool"),
+            },
+        )
+    }
+
+    // Reduces the values of every pooling window. The count passed to reduce
+    // includes padded positions if include_pad is set
+    #[inline]
+    pub fn _window_pool<F>(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+        reduce: F,
+    ) -> Tensor<DType>
+    where
+        F: Fn(&[DType], u32) -> DType,
+    {
         let N = self.get_dim_size(0);
         let C = self.get_dim_size(1);
         let D = self.get_sh();
@@ -279,6 +723,8 @@ where
         let o_size = get_size(&output_shape);
         let mut values = vec![zero(); o_size];
 
+        let mut window = Vec::with_capacity(kernel_size);
+
         // Iterate over all batches
         for n in 0..N {
             // Iterate over all output channels
@@ -290,8 +736,8 @@ where
                 output_indices[1] = c;
 
                 for o_ix in 0..output_size {
-                    let mut result = zero();
                     let mut count = 0;
+                    window.clear();
 
                     let mut kernel_indices = vec![0; data_rank];
 
@@ -315,8 +761,7 @@ where
                         }
 
                         if !skip {
-                            let x_i = self.get(&input_ix);
-                            result = result + x_i;
+                            window.push(self.get(&input_ix));
                         }
 
                         if !skip || include_pad {
@@ -326,12 +771,7 @@ where
                         increment_index(&mut kernel_indices, kernel_shape);
                     }
 
-                    match DType::from_u32(count) {
-                        Some(co) => result = result / co,
-                        None => panic!("DType can not represent kernel size in average pool"),
-                    }
-
-                    values[basis + o_ix] = result;
+                    values[basis + o_ix] = reduce(&window, count);
 
                     increment_index(&mut output_indices, &output_shape);
                 }
@@ -341,57 +781,212 @@ where
         Tensor::new(output_shape, output_strides, o_size, values)
     }
 
-    pub fn _pad(&self, pads: &Vec<usize>, mode: i32, value: DType) -> Tensor<DType> {
-        let rank = self.rank();
-
-        let mut output_shape = vec![0; rank];
-        for i in 0..rank {
-            output_shape[i] = self.get_dim_size(i) + pads[i] + pads[i + rank];
-        }
-        let output_strides = compute_strides(&output_shape);
-        let output_size = get_size(&output_shape);
+    // Returns the maximum of every window and its flat index into the input.
+    // The spatial part of the index is row major for storage_order 0 and
+    // column major for storage_order 1, padded positions are never selected.
+    // Pads have to be smaller than the kernel, so no window is only padding
+    pub fn _max_pool(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        dilations: &Vec<usize>,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> (Tensor<DType>, Tensor<u32>) {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let D = self.get_sh();
 
-        let mut values = vec![zero(); output_size];
+        let kernel_size = get_size(&kernel_shape);
+        let spatial_size = get_size_from(D, 2);
 
-        let mut ix = vec![0; rank];
-        let mut input_ix = vec![0; rank];
-        for i in 0..output_size {
-            let mut use_const = false;
-            for j in 0..rank {
-                if ix[j] < pads[j] {
-                    if mode == 0 {
-                        use_const = true;
-                        break;
-                    } else if mode == 1 {
-                        input_ix[j] = pads[j] - ix[j];
-                    } else {
-                        input_ix[j] = 0;
-                    }
-                } else {
-                    input_ix[j] = ix[j] - pads[j];
-                }
-                if input_ix[j] >= self.get_dim_size(j) {
-                    if mode == 0 {
-                        use_const = true;
-                        break;
-                    } else if mode == 1 {
-                        input_ix[j] = 2 * self.get_dim_size(j) - input_ix[j] - 2;
-                    } else {
-                        input_ix[j] = self.get_dim_size(j) - 1;
-                    }
-                }
-            }
+        let data_rank = self.rank() - 2;
 
-            if use_const {
-                values[i] = value;
-            } else {
-                values[i] = self.get(&input_ix);
+        for axis in 0..data_rank {
+            if pads[axis] >= kernel_shape[axis] || pads[axis + data_rank] >= kernel_shape[axis] {
+                panic!(
+                    "Pads of axis {} in max pool have to be smaller than the kernel size {}",
+                    axis, kernel_shape[axis]
+                );
             }
-
-            increment_index(&mut ix, &output_shape);
         }
 
-        Tensor::new(output_shape, output_strides, output_size, values)
+        let R = pool_output_size(D, kernel_shape, pads, dilations, strides, ceil_mode);
+        let output_size = get_size(&R);
+
+        let mut output_shape = vec![0; data_rank + 2];
+        output_shape[0] = N;
+        output_shape[1] = C;
+        for i in 0..data_rank {
+            output_shape[i + 2] = R[i];
+        }
+
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+        let mut indices = vec![0; o_size];
+
+        // Iterate over all batches
+        for n in 0..N {
+            // Iterate over all output channels
+            for c in 0..C {
+                let basis = output_strides[0] * n + output_strides[1] * c;
+                let input_basis = (n * C + c) * spatial_size;
+
+                let mut output_indices = vec![0; data_rank + 2];
+                output_indices[0] = n;
+                output_indices[1] = c;
+
+                for o_ix in 0..output_size {
+                    let mut found = false;
+                    let mut result = zero();
+                    let mut result_ix = 0;
+
+                    let mut kernel_indices = vec![0; data_rank];
+
+                    for _ in 0..kernel_size {
+                        let mut input_ix = vec![0; data_rank + 2];
+                        input_ix[0] = n;
+                        input_ix[1] = c;
+
+                        let mut skip = false;
+                        for axis in 0..data_rank {
+                            let ix = (output_indices[axis + 2] * strides[axis]) as i32
+                                + (kernel_indices[axis] * dilations[axis]) as i32
+                                - (pads[axis] as i32);
+
+                            if ix < 0 || ix >= D[axis + 2] as i32 {
+                                skip = true;
+                                break;
+                            }
+
+                            input_ix[axis + 2] = ix as usize;
+                        }
+
+                        if !skip {
+                            let x = self.get(&input_ix);
+                            if !found || x > result {
+                                found = true;
+                                result = x;
+                                result_ix = spatial_position(&input_ix, D, storage_order);
+                            }
+                        }
+
+                        increment_index(&mut kernel_indices, kernel_shape);
+                    }
+
+                    values[basis + o_ix] = result;
+                    indices[basis + o_ix] = (input_basis + result_ix) as u32;
+
+                    increment_index(&mut output_indices, &output_shape);
+                }
+            }
+        }
+
+        (
+            Tensor::new(output_shape.clone(), output_strides.clone(), o_size, values),
+            Tensor::new(output_shape, output_strides, o_size, indices),
+        )
+    }
+
+    // Kernel shape, pads and strides of a window covering all spatial dimensions
+    fn global_pool_parameters(&self) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+        let data_rank = self.rank() - 2;
+        (
+            self.get_sh()[2..].to_vec(),
+            vec![0; 2 * data_rank],
+            vec![1; data_rank],
+        )
+    }
+
+    pub fn global_average_pool(&self) -> Tensor<DType> {
+        let (kernel_shape, pads, strides) = self.global_pool_parameters();
+        self._average_pool(&kernel_shape, &pads, &strides, false)
+    }
+
+    pub fn global_max_pool(&self) -> Tensor<DType> {
+        let (kernel_shape, pads, strides) = self.global_pool_parameters();
+        let dilations = vec![1; strides.len()];
+        let (values, _) = self._max_pool(&kernel_shape, &pads, &strides, &dilations, false, 0);
+        values
+    }
+
+    // Pads in the layout [begins..., ends...]. Negative pads crop the axis first,
+    // the remaining positive pads are then filled according to the mode
+    pub fn _pad(&self, pads: &Vec<isize>, mode: PadMode, value: DType) -> Tensor<DType> {
+        let rank = self.rank();
+
+        let mut output_shape = vec![0; rank];
+        let mut sources = vec![vec![]; rank];
+        for i in 0..rank {
+            let size = self.get_dim_size(i) as isize;
+            let crop_begin = (-pads[i]).max(0);
+            let crop_end = (-pads[i + rank]).max(0);
+            let cropped = size - crop_begin - crop_end;
+            if cropped < 0 {
+                panic!("Negative pads exceed the size of axis {}", i);
+            }
+            let pad_begin = pads[i].max(0);
+            let output_size = cropped + pad_begin + pads[i + rank].max(0);
+            if cropped == 0 && output_size > 0 && mode != PadMode::Constant {
+                panic!("Can not pad an empty axis with a non constant mode");
+            }
+
+            output_shape[i] = output_size as usize;
+            for o in 0..output_size {
+                sources[i].push(
+                    mode.source_index(o - pad_begin, cropped as usize)
+                        .map(|x| x + crop_begin as usize),
+                );
+            }
+        }
+        let output_strides = compute_strides(&output_shape);
+        let output_size = get_size(&output_shape);
+
+        let mut values = vec![zero(); output_size];
+
+        let mut ix = vec![0; rank];
+        let mut input_ix = vec![0; rank];
+        for i in 0..output_size {
+            let mut use_const = false;
+            for j in 0..rank {
+                match sources[j][ix[j]] {
+                    Some(x) => input_ix[j] = x,
+                    None => {
+                        use_const = true;
+                        break;
+                    }
+                }
+            }
+
+            if use_const {
+                values[i] = value;
+            } else {
+                values[i] = self.get(&input_ix);
+            }
+
+            increment_index(&mut ix, &output_shape);
+        }
+
+        Tensor::new(output_shape, output_strides, output_size, values)
+    }
+
+    // Pads only the given axes, pads has the layout [begins..., ends...] over axes
+    pub fn _pad_axes(
+        &self,
+        pads: &Vec<isize>,
+        axes: &Vec<usize>,
+        mode: PadMode,
+        value: DType,
+    ) -> Tensor<DType> {
+        let rank = self.rank();
+        let mut all_pads = vec![0; 2 * rank];
+        for i in 0..axes.len() {
+            all_pads[axes[i]] = pads[i];
+            all_pads[axes[i] + rank] = pads[i + axes.len()];
+        }
+        self._pad(&all_pads, mode, value)
     }
 
     pub fn _upsample(&self, scales: &Vec<f32>) -> Tensor<DType> {
@@ -429,6 +1024,7 @@ where
     DType: Copy,
     DType: Num,
     DType: FromPrimitive,
+    DType: ToPrimitive,
     DType: PartialOrd,
 {
     pub fn conv(
@@ -458,7 +1054,7 @@ where
             group as usize,
             &_pads,
             &_strides,
-            activation,
+            Activation::from_u32(activation),
         );
     }
 
@@ -490,7 +1086,7 @@ where
             group as usize,
             &_pads,
             &_strides,
-            activation,
+            Activation::from_u32(activation),
         );
     }
 
@@ -516,6 +1112,168 @@ where
         return self._conv_transpose(kernel, &_dilations, group as usize, &_pads, &_strides);
     }
 
+    pub fn conv_fused(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            activation,
+        );
+    }
+
+    pub fn _conv_transpose_fused(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let result = self._conv_transpose(kernel, dilations, group, pads, strides);
+        let mut values = result.get_values().clone();
+        activation.apply(&mut values);
+        Tensor::new_from_shape(result.get_sh(), &values)
+    }
+
+    pub fn conv_transpose_fused(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_transpose_fused(
+            kernel,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            activation,
+        );
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_auto_pad(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) =
+            conv_auto_pad_parameters(self.get_sh(), kernel.get_sh(), dilations, strides, auto_pad);
+
+        return self._conv(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn average_pool_auto_pad(
+        &self,
+        kernel_shape: Uint32Array,
+        strides: Uint32Array,
+        auto_pad: u32,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) =
+            pool_auto_pad_parameters(self.get_sh(), kernel_shape, strides, auto_pad);
+
+        return self._average_pool(&_kernel_shape, &_pads, &_strides, include_pad);
+    }
+
+    pub fn conv_transpose_output_padding(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        let mut _output_padding: Vec<usize> = vec![0; output_padding.length() as usize];
+        for i in 0..output_padding.length() {
+            _output_padding[i as usize] = output_padding.get_index(i) as usize;
+        }
+
+        return self._conv_transpose_output_padding(
+            kernel,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            &_output_padding,
+        );
+    }
+
+    // An empty output_shape derives the pads from the auto pad mode only
+    // Mode: 0 == NOTSET, 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_transpose_auto_pad(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        output_padding: Uint32Array,
+        output_shape: Uint32Array,
+        auto_pad: u32,
+    ) -> Tensor<DType> {
+        let data_rank = dilations.length() as usize;
+        let mut _dilations: Vec<usize> = vec![0; data_rank];
+        let mut _strides: Vec<usize> = vec![0; data_rank];
+        let mut _output_padding: Vec<usize> = vec![0; data_rank];
+        let mut _output_shape: Vec<usize> = vec![0; output_shape.length() as usize];
+        for i in 0..dilations.length() {
+            _dilations[i as usize] = dilations.get_index(i) as usize;
+            _strides[i as usize] = strides.get_index(i) as usize;
+            _output_padding[i as usize] = output_padding.get_index(i) as usize;
+        }
+        for i in 0..output_shape.length() {
+            _output_shape[i as usize] = output_shape.get_index(i) as usize;
+        }
+
+        return self._conv_transpose_auto_pad(
+            kernel,
+            &_dilations,
+            group as usize,
+            &_strides,
+            &_output_padding,
+            if _output_shape.len() > 0 {
+                Some(&_output_shape)
+            } else {
+                None
+            },
+            AutoPad::from_u32(auto_pad),
+        );
+    }
+
     pub fn average_pool(
         &self,
         kernel_shape: Uint32Array,
@@ -537,26 +1295,793 @@ where
         return self._average_pool(&_kernel_shape, &_pads, &_strides, include_pad);
     }
 
-    // Mode: 0 == constant, 1 == reflect, 2 == edge
-    pub fn pad(&self, pads: Uint32Array, mode: i32, value: DType) -> Tensor<DType> {
+    // Mode: 0 == sequential, 1 == pairwise, 2 == kahan
+    pub fn average_pool_with_mode(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+        mode: u32,
+    ) -> Tensor<DType> {
+        let mut _kernel_shape: Vec<usize> = vec![0; kernel_shape.length() as usize];
         let mut _pads: Vec<usize> = vec![0; pads.length() as usize];
-        for i in 0..pads.length() {
+        let mut _strides: Vec<usize> = vec![0; strides.length() as usize];
+        for i in 0..kernel_shape.length() {
+            _kernel_shape[i as usize] = kernel_shape.get_index(i) as usize;
             _pads[i as usize] = pads.get_index(i) as usize;
+            _pads[(i + kernel_shape.length()) as usize] =
+                pads.get_index(i + kernel_shape.length()) as usize;
+            _strides[i as usize] = strides.get_index(i) as usize;
         }
-        return self._pad(&_pads, mode, value);
+
+        return self._average_pool_with_mode(
+            &_kernel_shape,
+            &_pads,
+            &_strides,
+            include_pad,
+            SumMode::from_u32(mode),
+        );
     }
 
-    pub fn upsample(&self, scales: Float32Array) -> Tensor<DType> {
-        let mut _scales: Vec<f32> = vec![0.0; scales.length() as usize];
-        for i in 0..scales.length() {
-            _scales[i as usize] = scales.get_index(i) as f32;
+    pub fn max_pool(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+        storage_order: u32,
+    ) -> (Tensor<DType>, Tensor<u32>) {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        let mut _dilations: Vec<usize> = vec![0; dilations.length() as usize];
+        for i in 0..dilations.length() {
+            _dilations[i as usize] = dilations.get_index(i) as usize;
         }
 
-        return self._upsample(&_scales);
+        return self._max_pool(
+            &_kernel_shape,
+            &_pads,
+            &_strides,
+            &_dilations,
+            ceil_mode,
+            storage_order,
+        );
     }
-}
 
-impl<DType> Tensor<DType>
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
+    pub fn pad(&self, pads: Uint32Array, mode: i32, value: DType) -> Tensor<DType> {
+        let mut _pads: Vec<isize> = vec![0; pads.length() as usize];
+        for i in 0..pads.length() {
+            _pads[i as usize] = pads.get_index(i) as isize;
+        }
+        return self._pad(&_pads, PadMode::from_i32(mode), value);
+    }
+
+    // An empty axes pads all axes
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
+    pub fn pad_axes(
+        &self,
+        pads: Int32Array,
+        axes: Uint32Array,
+        mode: i32,
+        value: DType,
+    ) -> Tensor<DType> {
+        let mut _pads: Vec<isize> = vec![0; pads.length() as usize];
+        for i in 0..pads.length() {
+            _pads[i as usize] = pads.get_index(i) as isize;
+        }
+        if axes.length() == 0 {
+            return self._pad(&_pads, PadMode::from_i32(mode), value);
+        }
+        let mut _axes: Vec<usize> = vec![0; axes.length() as usize];
+        for i in 0..axes.length() {
+            _axes[i as usize] = axes.get_index(i) as usize;
+        }
+        return self._pad_axes(&_pads, &_axes, PadMode::from_i32(mode), value);
+    }
+
+    pub fn upsample(&self, scales: Float32Array) -> Tensor<DType> {
+        let mut _scales: Vec<f32> = vec![0.0; scales.length() as usize];
+        for i in 0..scales.length() {
+            _scales[i as usize] = scales.get_index(i) as f32;
+        }
+
+        return self._upsample(&_scales);
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+{
+    // Gradient of _conv with respect to its input. The gradient of the output
+    // has to be taken before the activation
+    pub fn _conv_backward_input(
+        &self,
+        kernel: &Tensor<DType>,
+        input_shape: &Vec<usize>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let C = input_shape[1];
+        let CG = kernel.get_dim_size(1);
+        let data_rank = input_shape.len() - 2;
+
+        // Without groups this is a transposed convolution of the gradient with
+        // the kernel whose channel axes are swapped
+        if CG == C {
+            let mut transpose_pads = vec![0; 2 * data_rank];
+            let mut valid = true;
+            for axis in 0..data_rank {
+                let dkernel = (dilations[axis] * (kernel.get_dim_size(axis + 2) - 1) + 1) as i64;
+                let begin = dkernel - 1 - pads[axis] as i64;
+                let end = input_shape[axis + 2] as i64
+                    - (strides[axis] * (self.get_dim_size(axis + 2) - 1)) as i64
+                    - begin
+                    + dkernel
+                    - 2;
+                if begin < 0 || end < 0 {
+                    valid = false;
+                    break;
+                }
+                transpose_pads[axis] = begin as usize;
+                transpose_pads[axis + data_rank] = end as usize;
+            }
+
+            if valid {
+                let mut permutation: Vec<usize> = (0..data_rank + 2).collect();
+                permutation.swap(0, 1);
+                return self._conv_transpose(
+                    &kernel._transpose(&permutation),
+                    dilations,
+                    group,
+                    &transpose_pads,
+                    strides,
+                );
+            }
+        }
+
+        let mut values = vec![zero(); get_size(input_shape)];
+        visit_conv_positions(
+            input_shape,
+            kernel.get_sh(),
+            dilations,
+            group,
+            pads,
+            strides,
+            |o, k, i| values[i] = values[i] + self.get_ix(o) * kernel.get_ix(k),
+        );
+
+        Tensor::new_from_shape(input_shape, &values)
+    }
+
+    // Gradient of _conv with respect to the kernel, called on the input of the convolution
+    pub fn _conv_backward_kernel(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: &Vec<usize>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let mut values = vec![zero(); get_size(kernel_shape)];
+        visit_conv_positions(
+            self.get_sh(),
+            kernel_shape,
+            dilations,
+            group,
+            pads,
+            strides,
+            |o, k, i| values[k] = values[k] + grad.get_ix(o) * self.get_ix(i),
+        );
+
+        Tensor::new_from_shape(kernel_shape, &values)
+    }
+
+    // Gradient of _conv with respect to the bias, the sum over all axes but the channels
+    pub fn conv_backward_bias(&self) -> Tensor<DType> {
+        let M = self.get_dim_size(1);
+        let spatial_size = get_size_from(self.get_sh(), 2);
+
+        let mut values = vec![zero(); M];
+        for i in 0..self.size {
+            let m = (i / spatial_size) % M;
+            values[m] = values[m] + self.get_ix(i);
+        }
+
+        Tensor::new_from_shape(&vec![M], &values)
+    }
+
+    pub fn conv_backward_input(
+        &self,
+        kernel: &Tensor<DType>,
+        input_shape: Uint32Array,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        let mut _input_shape: Vec<usize> = vec![0; input_shape.length() as usize];
+        for i in 0..input_shape.length() {
+            _input_shape[i as usize] = input_shape.get_index(i) as usize;
+        }
+
+        return self._conv_backward_input(
+            kernel,
+            &_input_shape,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+        );
+    }
+
+    pub fn conv_backward_kernel(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: Uint32Array,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        let mut _kernel_shape: Vec<usize> = vec![0; kernel_shape.length() as usize];
+        for i in 0..kernel_shape.length() {
+            _kernel_shape[i as usize] = kernel_shape.get_index(i) as usize;
+        }
+
+        return self._conv_backward_kernel(
+            grad,
+            &_kernel_shape,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+        );
+    }
+
+    // Gradient of _average_pool, called on the input of the pooling
+    pub fn _average_pool_backward(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let D = self.get_sh();
+
+        let kernel_size = get_size(&kernel_shape);
+        let spatial_size = get_size_from(D, 2);
+
+        let data_rank = self.rank() - 2;
+
+        let R = conv_output_size(D, kernel_shape, pads, &vec![1; data_rank], strides, 0);
+        let output_size = get_size(&R);
+        let input_strides = compute_strides_no_zero(D);
+
+        let mut values = vec![zero(); self.size];
+        let mut window = Vec::with_capacity(kernel_size);
+
+        for n in 0..N {
+            for c in 0..C {
+                let input_base = (n * C + c) * spatial_size;
+                let output_base = (n * C + c) * output_size;
+
+                let mut output_indices = vec![0; data_rank];
+                for o_ix in 0..output_size {
+                    let mut count = 0;
+                    window.clear();
+
+                    let mut kernel_indices = vec![0; data_rank];
+                    for _ in 0..kernel_size {
+                        let mut position = input_base;
+                        let mut skip = false;
+                        for axis in 0..data_rank {
+                            let ix = output_indices[axis] * strides[axis] + kernel_indices[axis];
+                            if ix < pads[axis] || ix - pads[axis] >= D[axis + 2] {
+                                skip = true;
+                                break;
+                            }
+                            position += (ix - pads[axis]) * input_strides[axis + 2];
+                        }
+
+                        if !skip {
+                            window.push(position);
+                        }
+                        if !skip || include_pad {
+                            count += 1;
+                        }
+
+                        increment_index(&mut kernel_indices, kernel_shape);
+                    }
+
+                    let g = match DType::from_u32(count) {
+                        Some(co) => grad.get_ix(output_base + o_ix) / co,
+                        None => panic!("DType can not represent kernel size in average pool"),
+                    };
+                    for &position in &window {
+                        values[position] = values[position] + g;
+                    }
+
+                    increment_index(&mut output_indices, &R);
+                }
+            }
+        }
+
+        Tensor::new_from_shape(D, &values)
+    }
+
+    // Scatters the gradient of max pooling to the flat input positions
+    // returned by _max_pool with storage_order 0
+    pub fn _max_pool_backward_indices(
+        &self,
+        indices: &Tensor<u32>,
+        input_shape: &Vec<usize>,
+    ) -> Tensor<DType> {
+        let mut values = vec![zero(); get_size(input_shape)];
+        for i in 0..self.size {
+            let position = indices.get_ix(i) as usize;
+            values[position] = values[position] + self.get_ix(i);
+        }
+
+        Tensor::new_from_shape(input_shape, &values)
+    }
+
+    // Gradient of _max_pool, called on the input of the pooling. Every output
+    // passes its gradient to the first maximum of its window
+    pub fn _max_pool_backward(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        dilations: &Vec<usize>,
+        ceil_mode: bool,
+    ) -> Tensor<DType> {
+        let (_, indices) = self._max_pool(kernel_shape, pads, strides, dilations, ceil_mode, 0);
+        grad._max_pool_backward_indices(&indices, self.get_sh())
+    }
+
+    pub fn average_pool_backward(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        return self._average_pool_backward(grad, &_kernel_shape, &_pads, &_strides, include_pad);
+    }
+
+    pub fn max_pool_backward(
+        &self,
+        grad: &Tensor<DType>,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        dilations: Uint32Array,
+        ceil_mode: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        let mut _dilations: Vec<usize> = vec![0; dilations.length() as usize];
+        for i in 0..dilations.length() {
+            _dilations[i as usize] = dilations.get_index(i) as usize;
+        }
+
+        return self._max_pool_backward(
+            grad,
+            &_kernel_shape,
+            &_pads,
+            &_strides,
+            &_dilations,
+            ceil_mode,
+        );
+    }
+
+    pub fn max_pool_backward_indices(
+        &self,
+        indices: &Tensor<u32>,
+        input_shape: Uint32Array,
+    ) -> Tensor<DType> {
+        let mut _input_shape: Vec<usize> = vec![0; input_shape.length() as usize];
+        for i in 0..input_shape.length() {
+            _input_shape[i as usize] = input_shape.get_index(i) as usize;
+        }
+
+        return self._max_pool_backward_indices(indices, &_input_shape);
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+    DType: Widen,
+{
+    pub fn _conv_wide(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        _dilations: &Vec<usize>,
+        group: usize,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType::Wide> {
+        let wide_bias = match bias {
+            None => None,
+            Some(b) => Some(b._widen()),
+        };
+
+        self._widen()._conv(
+            &kernel._widen(),
+            wide_bias.as_ref(),
+            _dilations,
+            group,
+            _pads,
+            _strides,
+            activation,
+        )
+    }
+
+    pub fn _conv_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        _dilations: &Vec<usize>,
+        group: usize,
+        _pads: &Vec<usize>,
+        _strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(
+            &self._conv_wide(kernel, bias, _dilations, group, _pads, _strides, activation),
+        )
+    }
+
+    pub fn _average_pool_widened(
+        &self,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let sums = self._widen()._window_pool(
+            kernel_shape,
+            pads,
+            strides,
+            include_pad,
+            |window: &[DType::Wide], count: u32| match DType::Wide::from_u32(count) {
+                Some(co) => divide_rounded(sequential_sum(window), co),
+                None => panic!("DType can not represent kernel size in average pool"),
+            },
+        );
+        Tensor::_narrow(&sums)
+    }
+
+    pub fn global_average_pool_widened(&self) -> Tensor<DType> {
+        let (kernel_shape, pads, strides) = self.global_pool_parameters();
+        self._average_pool_widened(&kernel_shape, &pads, &strides, false)
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+    DType: Widen,
+{
+    pub fn conv_wide(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType::Wide> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+
+        return self._conv_wide(
+            kernel,
+            None,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    pub fn conv_with_bias_wide(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType::Wide> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+
+        return self._conv_wide(
+            kernel,
+            Some(bias),
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    pub fn conv_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(&self.conv_wide(kernel, dilations, group, pads, strides, activation))
+    }
+
+    pub fn conv_with_bias_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: &Tensor<DType>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(
+            &self.conv_with_bias_wide(kernel, bias, dilations, group, pads, strides, activation),
+        )
+    }
+
+    pub fn average_pool_widened(
+        &self,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let mut _kernel_shape: Vec<usize> = vec![0; kernel_shape.length() as usize];
+        let mut _pads: Vec<usize> = vec![0; pads.length() as usize];
+        let mut _strides: Vec<usize> = vec![0; strides.length() as usize];
+        for i in 0..kernel_shape.length() {
+            _kernel_shape[i as usize] = kernel_shape.get_index(i) as usize;
+            _pads[i as usize] = pads.get_index(i) as usize;
+            _pads[(i + kernel_shape.length()) as usize] =
+                pads.get_index(i + kernel_shape.length()) as usize;
+            _strides[i as usize] = strides.get_index(i) as usize;
+        }
+
+        return self._average_pool_widened(&_kernel_shape, &_pads, &_strides, include_pad);
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn conv_auto_pad_widened(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        strides: Uint32Array,
+        auto_pad: u32,
+        activation: u32,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) =
+            conv_auto_pad_parameters(self.get_sh(), kernel.get_sh(), dilations, strides, auto_pad);
+
+        return self._conv_widened(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            Activation::from_u32(activation),
+        );
+    }
+
+    // Mode: 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn average_pool_auto_pad_widened(
+        &self,
+        kernel_shape: Uint32Array,
+        strides: Uint32Array,
+        auto_pad: u32,
+        include_pad: bool,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) =
+            pool_auto_pad_parameters(self.get_sh(), kernel_shape, strides, auto_pad);
+
+        return self._average_pool_widened(&_kernel_shape, &_pads, &_strides, include_pad);
+    }
+}
+
+// Calls visit(output_position, kernel_position, input_position) for every
+// pair of output and kernel element of _conv that reads an input element
+// outside of the padding. Positions are flat indices into the output, the
+// kernel and the input. Kernel channel cg of output channel m reads input
+// channel (m * CG + cg) % C, as in _conv
+fn visit_conv_positions<F>(
+    input_shape: &Vec<usize>,
+    kernel_shape: &Vec<usize>,
+    dilations: &Vec<usize>,
+    group: usize,
+    pads: &Vec<usize>,
+    strides: &Vec<usize>,
+    mut visit: F,
+) where
+    F: FnMut(usize, usize, usize),
+{
+    let N = input_shape[0];
+    let C = input_shape[1];
+    let M = kernel_shape[0];
+    let CG = kernel_shape[1];
+    if CG * group != C {
+        panic!(
+            "Kernel with {} channels does not match {} input channels in {} groups",
+            CG, C, group
+        );
+    }
+
+    let data_rank = input_shape.len() - 2;
+    let kernel_size = get_size_from(kernel_shape, 2);
+    let spatial_size = get_size_from(input_shape, 2);
+
+    let R = conv_output_size(input_shape, kernel_shape, pads, dilations, strides, 2);
+    let output_size = get_size(&R);
+    let input_strides = compute_strides_no_zero(input_shape);
+
+    let mut output_indices = vec![0; data_rank];
+    let mut kernel_indices = vec![0; data_rank];
+    let kernel_spatial_shape = kernel_shape[2..].to_vec();
+
+    // Spatial input position of every output and kernel position pair
+    let mut positions = vec![None; output_size * kernel_size];
+    for o_ix in 0..output_size {
+        for kernel_ix in 0..kernel_size {
+            let mut position = 0;
+            let mut inside = true;
+            for axis in 0..data_rank {
+                let ix =
+                    output_indices[axis] * strides[axis] + kernel_indices[axis] * dilations[axis];
+                if ix < pads[axis] || ix - pads[axis] >= input_shape[axis + 2] {
+                    inside = false;
+                    break;
+                }
+                position += (ix - pads[axis]) * input_strides[axis + 2];
+            }
+            if inside {
+                positions[o_ix * kernel_size + kernel_ix] = Some(position);
+            }
+            increment_index(&mut kernel_indices, &kernel_spatial_shape);
+        }
+        increment_index(&mut output_indices, &R);
+    }
+
+    for n in 0..N {
+        for m in 0..M {
+            let output_base = (n * M + m) * output_size;
+            for cg in 0..CG {
+                let c = (m * CG + cg) % C;
+                let input_base = (n * C + c) * spatial_size;
+                let kernel_base = (m * CG + cg) * kernel_size;
+
+                for o_ix in 0..output_size {
+                    for kernel_ix in 0..kernel_size {
+                        if let Some(position) = positions[o_ix * kernel_size + kernel_ix] {
+                            visit(
+                                output_base + o_ix,
+                                kernel_base + kernel_ix,
+                                input_base + position,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Position of the spatial part of index, row major for storage_order 0
+// and column major for storage_order 1
+fn spatial_position(index: &Vec<usize>, shape: &Vec<usize>, storage_order: u32) -> usize {
+    let mut position = 0;
+    if storage_order == 0 {
+        for axis in 2..shape.len() {
+            position = position * shape[axis] + index[axis];
+        }
+    } else {
+        for axis in (2..shape.len()).rev() {
+            position = position * shape[axis] + index[axis];
+        }
+    }
+    position
+}
+
+pub fn conv_parameters(
+    dilations: Uint32Array,
+    pads: Uint32Array,
+    strides: Uint32Array,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let mut _dilations: Vec<usize> = vec![0; dilations.length() as usize];
+    let mut _pads: Vec<usize> = vec![0; pads.length() as usize];
+    let mut _strides: Vec<usize> = vec![0; strides.length() as usize];
+    for i in 0..dilations.length() {
+        _dilations[i as usize] = dilations.get_index(i) as usize;
+        _pads[i as usize] = pads.get_index(i) as usize;
+        _pads[(i + dilations.length()) as usize] = pads.get_index(i + dilations.length()) as usize;
+        _strides[i as usize] = strides.get_index(i) as usize;
+    }
+    (_dilations, _pads, _strides)
+}
+
+// Dilations, pads and strides of a convolution with the pads resolved from the auto pad mode
+pub fn conv_auto_pad_parameters(
+    input_shape: &Vec<usize>,
+    kernel_shape: &Vec<usize>,
+    dilations: Uint32Array,
+    strides: Uint32Array,
+    auto_pad: u32,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let _dilations = usize_vec(&dilations);
+    let _strides = usize_vec(&strides);
+    let _pads = conv_auto_pads(
+        input_shape,
+        kernel_shape,
+        &_dilations,
+        &_strides,
+        AutoPad::from_u32(auto_pad),
+    );
+    (_dilations, _pads, _strides)
+}
+
+// Kernel shape, pads and strides of a pooling with the pads resolved from the auto pad mode
+pub fn pool_auto_pad_parameters(
+    input_shape: &Vec<usize>,
+    kernel_shape: Uint32Array,
+    strides: Uint32Array,
+    auto_pad: u32,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let _kernel_shape = usize_vec(&kernel_shape);
+    let _strides = usize_vec(&strides);
+    let _pads = pool_auto_pads(
+        input_shape,
+        &_kernel_shape,
+        &_strides,
+        AutoPad::from_u32(auto_pad),
+    );
+    (_kernel_shape, _pads, _strides)
+}
+
+impl<DType> Tensor<DType>
 where
     DType: Clone,
     DType: Num,
@@ -593,3 +2118,59 @@ where
         Tensor::new(result_shape, result_strides, self.size, values)
     }
 }
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: PartialOrd,
+    DType: Float,
+{
+    // Lp norm of every window for p >= 1, padded positions count as zero
+    pub fn _lp_pool(
+        &self,
+        p: u32,
+        kernel_shape: &Vec<usize>,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<DType> {
+        if p == 0 {
+            panic!("p of lp pool has to be at least 1");
+        }
+        let inv_p = match DType::from_u32(p) {
+            Some(p) => p.recip(),
+            None => panic!("DType can not represent p in lp pool"),
+        };
+
+        self._window_pool(
+            kernel_shape,
+            pads,
+            strides,
+            false,
+            |window: &[DType], _count: u32| {
+                let mut result: DType = zero();
+                for i in 0..window.len() {
+                    result = result + window[i].abs().powi(p as i32);
+                }
+                result.powf(inv_p)
+            },
+        )
+    }
+
+    pub fn lp_pool(
+        &self,
+        p: u32,
+        kernel_shape: Uint32Array,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<DType> {
+        let (_kernel_shape, _pads, _strides) = conv_parameters(kernel_shape, pads, strides);
+        return self._lp_pool(p, &_kernel_shape, &_pads, &_strides);
+    }
+
+    pub fn global_lp_pool(&self, p: u32) -> Tensor<DType> {
+        let (kernel_shape, pads, strides) = self.global_pool_parameters();
+        self._lp_pool(p, &kernel_shape, &pads, &strides)
+    }
+}
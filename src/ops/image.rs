@@ -0,0 +1,170 @@
+use crate::ops::resize::*;
+use crate::shape::*;
+use crate::tensor::*;
+use js_sys::Float32Array;
+use js_sys::Uint32Array;
+use js_sys::Uint8ClampedArray;
+
+// Per channel parameter, a single entry applies to all channels and no entry gives the default
+fn channel_parameter(values: &Vec<f32>, channel: usize, default: f32) -> f32 {
+    match values.len() {
+        0 => default,
+        1 => values[0],
+        _ => values[channel],
+    }
+}
+
+impl Tensor<f32> {
+    // Tensor of shape [1, C, H, W], or [1, H, W, C] with channels_last, from RGBA pixels of
+    // an image with the given width and height, such as canvas ImageData.
+    // channels selects the RGBA components in order, [0, 1, 2] for RGB and [2, 1, 0] for BGR.
+    // Every value is (pixel * scale - mean) / std. The centered crop of size
+    // crop_height x crop_width, or the whole image for a size of 0, is resized bilinearly
+    // with half pixel coordinates to output_height x output_width
+    pub fn _from_rgba(
+        pixels: &Vec<u8>,
+        width: usize,
+        height: usize,
+        channels: &Vec<usize>,
+        scale: f32,
+        mean: &Vec<f32>,
+        std: &Vec<f32>,
+        crop_height: usize,
+        crop_width: usize,
+        output_height: usize,
+        output_width: usize,
+        channels_last: bool,
+    ) -> Tensor<f32> {
+        if pixels.len() != width * height * 4 {
+            panic!(
+                "Expected {} RGBA values for an image of size {}x{}",
+                width * height * 4,
+                width,
+                height
+            );
+        }
+        if channels.iter().any(|&c| c > 3) {
+            panic!("Channels have to select one of the RGBA components");
+        }
+
+        let crop_height = if crop_height == 0 {
+            height
+        } else {
+            crop_height.min(height)
+        };
+        let crop_width = if crop_width == 0 {
+            width
+        } else {
+            crop_width.min(width)
+        };
+        let top = (height - crop_height) / 2;
+        let left = (width - crop_width) / 2;
+
+        let axis_taps =
+            |in_size: usize, out_size: usize, offset: usize| -> Vec<Vec<(usize, f64)>> {
+                let scale = out_size as f64 / in_size as f64;
+                (0..out_size)
+                    .map(|i| {
+                        let x = CoordinateTransform::HalfPixel
+                            .to_input(i, scale, in_size, out_size, 0., 1.);
+                        interpolation_taps(x, in_size, ResizeMode::Linear)
+                            .into_iter()
+                            .map(|(ix, weight)| (ix + offset, weight))
+                            .collect()
+                    })
+                    .collect()
+            };
+        let y_taps = axis_taps(crop_height, output_height, top);
+        let x_taps = axis_taps(crop_width, output_width, left);
+
+        let C = channels.len();
+        let mut multipliers = vec![0.; C];
+        let mut offsets = vec![0.; C];
+        for c in 0..C {
+            let std = channel_parameter(std, c, 1.) as f64;
+            multipliers[c] = scale as f64 / std;
+            offsets[c] = channel_parameter(mean, c, 0.) as f64 / std;
+        }
+
+        let output_shape = if channels_last {
+            vec![1, output_height, output_width, C]
+        } else {
+            vec![1, C, output_height, output_width]
+        };
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![0.; o_size];
+
+        let plane = output_height * output_width;
+        for oy in 0..output_height {
+            for ox in 0..output_width {
+                for c in 0..C {
+                    let mut pixel = 0.;
+                    for &(y, y_weight) in y_taps[oy].iter() {
+                        for &(x, x_weight) in x_taps[ox].iter() {
+                            pixel += y_weight
+                                * x_weight
+                                * pixels[(y * width + x) * 4 + channels[c]] as f64;
+                        }
+                    }
+                    let position = oy * output_width + ox;
+                    let index = if channels_last {
+                        position * C + c
+                    } else {
+                        c * plane + position
+                    };
+                    values[index] = (pixel * multipliers[c] - offsets[c]) as f32;
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    pub fn from_rgba(
+        pixels: Uint8ClampedArray,
+        width: u32,
+        height: u32,
+        channels: Uint32Array,
+        scale: f32,
+        mean: Float32Array,
+        std: Float32Array,
+        crop_height: u32,
+        crop_width: u32,
+        output_height: u32,
+        output_width: u32,
+        channels_last: bool,
+    ) -> Tensor<f32> {
+        let mut _pixels: Vec<u8> = vec![0; pixels.length() as usize];
+        for i in 0..pixels.length() {
+            _pixels[i as usize] = pixels.get_index(i);
+        }
+        let mut _channels: Vec<usize> = vec![0; channels.length() as usize];
+        for i in 0..channels.length() {
+            _channels[i as usize] = channels.get_index(i) as usize;
+        }
+        let mut _mean: Vec<f32> = vec![0.0; mean.length() as usize];
+        for i in 0..mean.length() {
+            _mean[i as usize] = mean.get_index(i);
+        }
+        let mut _std: Vec<f32> = vec![0.0; std.length() as usize];
+        for i in 0..std.length() {
+            _std[i as usize] = std.get_index(i);
+        }
+
+        Tensor::_from_rgba(
+            &_pixels,
+            width as usize,
+            height as usize,
+            &_channels,
+            scale,
+            &_mean,
+            &_std,
+            crop_height as usize,
+            crop_width as usize,
+            output_height as usize,
+            output_width as usize,
+            channels_last,
+        )
+    }
+}
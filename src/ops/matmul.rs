@@ -1,7 +1,11 @@
+use crate::ops::accumulate::*;
+use crate::ops::activation::Activation;
 use crate::shape::*;
 use crate::tensor::*;
 use num_traits::zero;
+use num_traits::FromPrimitive;
 use num_traits::Num;
+use num_traits::ToPrimitive;
 
 impl<DType> Tensor<DType>
 where
@@ -89,11 +93,12 @@ where
 
             for n in 0..N {
                 for m in 0..M {
+                    let a = self.get_ix(a_base + m * a_m_mult + n * a_n_mult);
+                    let y_row = y_base + m * O;
+                    let b_row = b_base + n * b_n_mult;
                     for o in 0..O {
-                        values[y_base + m * O + o] = values[y_base + m * O + o]
-                            + (self.get_ix(a_base + m * a_m_mult + n * a_n_mult)
-                                * b.get_ix(b_base + n * b_n_mult + o * b_o_mult))
-                                * alpha;
+                        values[y_row + o] =
+                            values[y_row + o] + (a * b.get_ix(b_row + o * b_o_mult)) * alpha;
                     }
                 }
             }
@@ -162,3 +167,111 @@ where
         return self._gemm(other, a_transpose, b_transpose, alpha, Some(c), beta);
     }
 }
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: PartialOrd,
+{
+    pub fn gemm_fused(
+        &self,
+        other: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+        c: Option<&Tensor<DType>>,
+        beta: DType,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let result = self._gemm(other, a_transpose, b_transpose, alpha, c, beta);
+        let mut values = result.get_values().clone();
+        activation.apply(&mut values);
+        Tensor::new_from_shape(result.get_sh(), &values)
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: Widen,
+{
+    pub fn _gemm_wide(
+        &self,
+        b: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+        c: Option<&Tensor<DType>>,
+        beta: DType,
+    ) -> Tensor<DType::Wide> {
+        let wide_c = match c {
+            None => None,
+            Some(c_) => Some(c_._widen()),
+        };
+
+        self._widen()._gemm(
+            &b._widen(),
+            a_transpose,
+            b_transpose,
+            alpha.widen(),
+            wide_c.as_ref(),
+            beta.widen(),
+        )
+    }
+
+    pub fn matmul_wide(&self, other: &Tensor<DType>) -> Tensor<DType::Wide> {
+        self._widen().matmul(&other._widen())
+    }
+
+    pub fn matmul_widened(&self, other: &Tensor<DType>) -> Tensor<DType> {
+        Tensor::_narrow(&self.matmul_wide(other))
+    }
+
+    pub fn gemm_wide(
+        &self,
+        other: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+    ) -> Tensor<DType::Wide> {
+        return self._gemm_wide(other, a_transpose, b_transpose, alpha, None, zero());
+    }
+
+    pub fn gemm_widened(
+        &self,
+        other: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(&self.gemm_wide(other, a_transpose, b_transpose, alpha))
+    }
+
+    pub fn gemm_with_c_wide(
+        &self,
+        other: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+        c: &Tensor<DType>,
+        beta: DType,
+    ) -> Tensor<DType::Wide> {
+        return self._gemm_wide(other, a_transpose, b_transpose, alpha, Some(c), beta);
+    }
+
+    pub fn gemm_with_c_widened(
+        &self,
+        other: &Tensor<DType>,
+        a_transpose: bool,
+        b_transpose: bool,
+        alpha: DType,
+        c: &Tensor<DType>,
+        beta: DType,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(&self.gemm_with_c_wide(other, a_transpose, b_transpose, alpha, c, beta))
+    }
+}
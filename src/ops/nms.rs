@@ -0,0 +1,266 @@
+use crate::tensor::*;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoxFormat {
+    // (y1, x1, y2, x2) of any two diagonal corners
+    Corners,
+    // (x_center, y_center, width, height)
+    Center,
+}
+
+impl BoxFormat {
+    // Center point box: 0 == corners, 1 == center
+    pub fn from_u32(center_point_box: u32) -> BoxFormat {
+        match center_point_box {
+            0 => BoxFormat::Corners,
+            1 => BoxFormat::Center,
+            _ => panic!("Unknown center point box {}", center_point_box),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoftNmsMethod {
+    Linear,
+    // Sigma of the gaussian penalty
+    Gaussian(f32),
+}
+
+impl SoftNmsMethod {
+    // Method: 0 == linear, 1 == gaussian(sigma)
+    pub fn from_parameters(method: u32, sigma: f32) -> SoftNmsMethod {
+        match method {
+            0 => SoftNmsMethod::Linear,
+            1 => SoftNmsMethod::Gaussian(sigma),
+            _ => panic!("Unknown soft nms method {}", method),
+        }
+    }
+
+    // Factor the score of a box is multiplied with when a box overlapping it with
+    // the given iou is selected. The linear method only decays above iou_threshold
+    pub fn decay(&self, iou: f64, iou_threshold: f64) -> f64 {
+        match *self {
+            SoftNmsMethod::Linear => {
+                if iou > iou_threshold {
+                    1. - iou
+                } else {
+                    1.
+                }
+            }
+            SoftNmsMethod::Gaussian(sigma) => (-iou * iou / sigma as f64).exp(),
+        }
+    }
+}
+
+// Intersection over union of two boxes given as [y_min, x_min, y_max, x_max].
+// Boxes without area do not overlap anything
+pub fn intersection_over_union(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+    let area_a = (a[2] - a[0]) * (a[3] - a[1]);
+    let area_b = (b[2] - b[0]) * (b[3] - b[1]);
+    if area_a <= 0. || area_b <= 0. {
+        return 0.;
+    }
+
+    let height = a[2].min(b[2]) - a[0].max(b[0]);
+    let width = a[3].min(b[3]) - a[1].max(b[1]);
+    if height <= 0. || width <= 0. {
+        return 0.;
+    }
+    let intersection = height * width;
+    intersection / (area_a + area_b - intersection)
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: ToPrimitive,
+{
+    // Box at index of batch as [y_min, x_min, y_max, x_max], on boxes of shape [B, N, 4]
+    fn box_corners(&self, batch: usize, index: usize, format: BoxFormat) -> [f64; 4] {
+        let base = (batch * self.get_dim_size(1) + index) * 4;
+        let v: Vec<f64> = (0..4)
+            .map(|i| self.get_ix(base + i).to_f64().unwrap())
+            .collect();
+        match format {
+            BoxFormat::Corners => [
+                v[0].min(v[2]),
+                v[1].min(v[3]),
+                v[0].max(v[2]),
+                v[1].max(v[3]),
+            ],
+            BoxFormat::Center => [
+                v[1] - v[3] / 2.,
+                v[0] - v[2] / 2.,
+                v[1] + v[3] / 2.,
+                v[0] + v[2] / 2.,
+            ],
+        }
+    }
+
+    // Boxes of class in batch scoring above score_threshold as (index, score),
+    // ordered by descending score and ascending index for equal scores
+    fn nms_candidates(
+        &self,
+        batch: usize,
+        class: usize,
+        score_threshold: f64,
+    ) -> Vec<(usize, f64)> {
+        let num_boxes = self.get_dim_size(2);
+        let base = (batch * self.get_dim_size(1) + class) * num_boxes;
+        let mut candidates: Vec<(usize, f64)> = (0..num_boxes)
+            .map(|i| (i, self.get_ix(base + i).to_f64().unwrap()))
+            .filter(|c| c.1 > score_threshold)
+            .collect();
+        // Stable, so equal scores keep their index order
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        candidates
+    }
+
+    // ONNX NonMaxSuppression on boxes of shape [B, N, 4] with scores of shape [B, C, N].
+    // Returns the selected indices of shape [num_selected, 3] as (batch, class, box),
+    // ordered by batch, class and descending score
+    pub fn _non_max_suppression(
+        &self,
+        scores: &Tensor<DType>,
+        max_output_boxes_per_class: usize,
+        iou_threshold: f64,
+        score_threshold: f64,
+        format: BoxFormat,
+    ) -> Tensor<u32> {
+        let num_batches = self.get_dim_size(0);
+        let num_classes = scores.get_dim_size(1);
+        if scores.get_dim_size(0) != num_batches || scores.get_dim_size(2) != self.get_dim_size(1) {
+            panic!("Boxes and scores of non max suppression do not match");
+        }
+
+        let mut values = Vec::new();
+        for b in 0..num_batches {
+            for c in 0..num_classes {
+                let mut selected: Vec<[f64; 4]> = Vec::new();
+                for (index, _) in scores.nms_candidates(b, c, score_threshold) {
+                    if selected.len() >= max_output_boxes_per_class {
+                        break;
+                    }
+                    let corners = self.box_corners(b, index, format);
+                    if selected
+                        .iter()
+                        .all(|s| intersection_over_union(s, &corners) <= iou_threshold)
+                    {
+                        selected.push(corners);
+                        values.extend_from_slice(&[b as u32, c as u32, index as u32]);
+                    }
+                }
+            }
+        }
+
+        let num_selected = values.len() / 3;
+        Tensor::new_from_shape(&vec![num_selected, 3], &values)
+    }
+
+    // Soft-NMS on boxes of shape [B, N, 4] with scores of shape [B, C, N]. Instead of
+    // discarding overlapping boxes their scores are decayed, boxes whose score drops
+    // to score_threshold or below are discarded. Returns the selected indices as in
+    // non max suppression and the decayed score of every selected box
+    pub fn _soft_non_max_suppression(
+        &self,
+        scores: &Tensor<DType>,
+        max_output_boxes_per_class: usize,
+        iou_threshold: f64,
+        score_threshold: f64,
+        format: BoxFormat,
+        method: SoftNmsMethod,
+    ) -> (Tensor<u32>, Tensor<DType>)
+    where
+        DType: FromPrimitive,
+    {
+        let num_batches = self.get_dim_size(0);
+        let num_classes = scores.get_dim_size(1);
+        if scores.get_dim_size(0) != num_batches || scores.get_dim_size(2) != self.get_dim_size(1) {
+            panic!("Boxes and scores of non max suppression do not match");
+        }
+
+        let mut indices = Vec::new();
+        let mut selected_scores = Vec::new();
+        for b in 0..num_batches {
+            for c in 0..num_classes {
+                let mut remaining: Vec<(usize, f64, [f64; 4])> = scores
+                    .nms_candidates(b, c, score_threshold)
+                    .into_iter()
+                    .map(|(index, score)| (index, score, self.box_corners(b, index, format)))
+                    .collect();
+
+                let mut num_selected = 0;
+                while num_selected < max_output_boxes_per_class && remaining.len() > 0 {
+                    let mut best = 0;
+                    for i in 1..remaining.len() {
+                        if remaining[i].1 > remaining[best].1 {
+                            best = i;
+                        }
+                    }
+                    let (index, score, corners) = remaining.remove(best);
+                    indices.extend_from_slice(&[b as u32, c as u32, index as u32]);
+                    selected_scores.push(DType::from_f64(score).unwrap());
+                    num_selected += 1;
+
+                    for candidate in remaining.iter_mut() {
+                        let iou = intersection_over_union(&corners, &candidate.2);
+                        candidate.1 *= method.decay(iou, iou_threshold);
+                    }
+                    remaining.retain(|candidate| candidate.1 > score_threshold);
+                }
+            }
+        }
+
+        let num_selected = selected_scores.len();
+        (
+            Tensor::new_from_shape(&vec![num_selected, 3], &indices),
+            Tensor::new_from_shape(&vec![num_selected], &selected_scores),
+        )
+    }
+
+    // Center point box: 0 == (y1, x1, y2, x2), 1 == (x_center, y_center, width, height)
+    pub fn non_max_suppression(
+        &self,
+        scores: &Tensor<DType>,
+        max_output_boxes_per_class: u32,
+        iou_threshold: f32,
+        score_threshold: f32,
+        center_point_box: u32,
+    ) -> Tensor<u32> {
+        self._non_max_suppression(
+            scores,
+            max_output_boxes_per_class as usize,
+            iou_threshold as f64,
+            score_threshold as f64,
+            BoxFormat::from_u32(center_point_box),
+        )
+    }
+
+    // Center point box: 0 == (y1, x1, y2, x2), 1 == (x_center, y_center, width, height)
+    // Method: 0 == linear, 1 == gaussian(sigma)
+    pub fn soft_non_max_suppression(
+        &self,
+        scores: &Tensor<DType>,
+        max_output_boxes_per_class: u32,
+        iou_threshold: f32,
+        score_threshold: f32,
+        center_point_box: u32,
+        method: u32,
+        sigma: f32,
+    ) -> (Tensor<u32>, Tensor<DType>)
+    where
+        DType: FromPrimitive,
+    {
+        self._soft_non_max_suppression(
+            scores,
+            max_output_boxes_per_class as usize,
+            iou_threshold as f64,
+            score_threshold as f64,
+            BoxFormat::from_u32(center_point_box),
+            SoftNmsMethod::from_parameters(method, sigma),
+        )
+    }
+}
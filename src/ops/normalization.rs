@@ -0,0 +1,471 @@
+use crate::shape::*;
+use crate::tensor::*;
+use num_traits::zero;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: Float,
+    DType: FromPrimitive,
+{
+    // Mean and inverse standard deviation of every continuous block of
+    // block_size elements, computed in two passes
+    pub fn block_statistics(&self, block_size: usize, epsilon: DType) -> (Vec<DType>, Vec<DType>) {
+        let blocks = self.size / block_size;
+        let n = match DType::from_usize(block_size) {
+            Some(n) => n,
+            None => panic!("Can not represent normalization size for given dtype"),
+        };
+
+        let mut mean = vec![zero(); blocks];
+        let mut inv_std = vec![zero(); blocks];
+        for b in 0..blocks {
+            let base = b * block_size;
+
+            let mut sum: DType = zero();
+            for j in 0..block_size {
+                sum = sum + self.get_ix(base + j);
+            }
+            let m = sum / n;
+
+            let mut square_sum: DType = zero();
+            for j in 0..block_size {
+                let d = self.get_ix(base + j) - m;
+                square_sum = square_sum + d * d;
+            }
+
+            mean[b] = m;
+            inv_std[b] = (square_sum / n + epsilon).sqrt().recip();
+        }
+
+        (mean, inv_std)
+    }
+
+    // Normalizes every continuous block of block_size elements, param_index
+    // maps a block and the offset within it to the scale and bias element
+    #[inline]
+    pub fn normalize_blocks<F>(
+        &self,
+        block_size: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+        param_index: F,
+    ) -> Tensor<DType>
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let (mean, inv_std) = self.block_statistics(block_size, epsilon);
+
+        let mut values = vec![zero(); self.size];
+        for b in 0..mean.len() {
+            let base = b * block_size;
+            for j in 0..block_size {
+                let k = param_index(b, j);
+                let normalized = (self.get_ix(base + j) - mean[b]) * inv_std[b];
+                values[base + j] = normalized * scale.get_ix(k) + bias.get_ix(k);
+            }
+        }
+
+        Tensor::new(
+            self.get_sh().to_vec(),
+            compute_strides(self.get_sh()),
+            self.size,
+            values,
+        )
+    }
+
+    // Returns the gradients for the input, the scale and the bias
+    #[inline]
+    pub fn normalize_blocks_backward<F>(
+        &self,
+        grad: &Tensor<DType>,
+        block_size: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        param_index: F,
+    ) -> (Tensor<DType>, Tensor<DType>, Tensor<DType>)
+    where
+        F: Fn(usize, usize) -> usize,
+    {
+        let (mean, inv_std) = self.block_statistics(block_size, epsilon);
+        let n = DType::from_usize(block_size).unwrap();
+
+        let mut values = vec![zero(); self.size];
+        let mut scale_grad = vec![zero(); scale.size];
+        let mut bias_grad = vec![zero(); scale.size];
+        for b in 0..mean.len() {
+            let base = b * block_size;
+
+            let mut sum_dnorm = zero();
+            let mut sum_dnorm_norm = zero();
+            for j in 0..block_size {
+                let k = param_index(b, j);
+                let g = grad.get_ix(base + j);
+                let normalized = (self.get_ix(base + j) - mean[b]) * inv_std[b];
+                let dnorm = g * scale.get_ix(k);
+
+                scale_grad[k] = scale_grad[k] + g * normalized;
+                bias_grad[k] = bias_grad[k] + g;
+                sum_dnorm = sum_dnorm + dnorm;
+                sum_dnorm_norm = sum_dnorm_norm + dnorm * normalized;
+            }
+
+            for j in 0..block_size {
+                let k = param_index(b, j);
+                let normalized = (self.get_ix(base + j) - mean[b]) * inv_std[b];
+                let dnorm = grad.get_ix(base + j) * scale.get_ix(k);
+                values[base + j] =
+                    inv_std[b] / n * (n * dnorm - sum_dnorm - normalized * sum_dnorm_norm);
+            }
+        }
+
+        (
+            Tensor::new(
+                self.get_sh().to_vec(),
+                compute_strides(self.get_sh()),
+                self.size,
+                values,
+            ),
+            Tensor::new_from_shape(scale.get_sh(), &scale_grad),
+            Tensor::new_from_shape(scale.get_sh(), &bias_grad),
+        )
+    }
+
+    // Normalizes over all axes starting at axis, scale and bias have the
+    // shape of these axes
+    pub fn layer_norm(
+        &self,
+        axis: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+    ) -> Tensor<DType> {
+        let block_size = get_size_from(self.get_sh(), axis);
+        self.normalize_blocks(block_size, epsilon, scale, bias, |_b, j| j)
+    }
+
+    pub fn layer_norm_backward(
+        &self,
+        grad: &Tensor<DType>,
+        axis: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+    ) -> (Tensor<DType>, Tensor<DType>, Tensor<DType>) {
+        let block_size = get_size_from(self.get_sh(), axis);
+        self.normalize_blocks_backward(grad, block_size, epsilon, scale, |_b, j| j)
+    }
+
+    // Normalizes every channel of every batch over the spatial dimensions,
+    // scale and bias have shape [C]
+    pub fn instance_norm(
+        &self,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+    ) -> Tensor<DType> {
+        let channels = self.get_dim_size(1);
+        let block_size = get_size_from(self.get_sh(), 2);
+        self.normalize_blocks(block_size, epsilon, scale, bias, |b, _j| b % channels)
+    }
+
+    pub fn instance_norm_backward(
+        &self,
+        grad: &Tensor<DType>,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+    ) -> (Tensor<DType>, Tensor<DType>, Tensor<DType>) {
+        let channels = self.get_dim_size(1);
+        let block_size = get_size_from(self.get_sh(), 2);
+        self.normalize_blocks_backward(grad, block_size, epsilon, scale, |b, _j| b % channels)
+    }
+
+    // Normalizes groups of C / groups channels together,
+    // scale and bias have shape [C]
+    pub fn group_norm(
+        &self,
+        groups: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+    ) -> Tensor<DType> {
+        let channels = self.get_dim_size(1);
+        if channels % groups != 0 {
+            panic!("Number of channels has to be divisible by the number of groups");
+        }
+        let channels_per_group = channels / groups;
+        let spatial_size = get_size_from(self.get_sh(), 2);
+
+        self.normalize_blocks(
+            channels_per_group * spatial_size,
+            epsilon,
+            scale,
+            bias,
+            |b, j| (b % groups) * channels_per_group + j / spatial_size,
+        )
+    }
+
+    pub fn group_norm_backward(
+        &self,
+        grad: &Tensor<DType>,
+        groups: usize,
+        epsilon: DType,
+        scale: &Tensor<DType>,
+    ) -> (Tensor<DType>, Tensor<DType>, Tensor<DType>) {
+        let channels = self.get_dim_size(1);
+        if channels % groups != 0 {
+            panic!("Number of channels has to be divisible by the number of groups");
+        }
+        let channels_per_group = channels / groups;
+        let spatial_size = get_size_from(self.get_sh(), 2);
+
+        self.normalize_blocks_backward(
+            grad,
+            channels_per_group * spatial_size,
+            epsilon,
+            scale,
+            |b, j| (b % groups) * channels_per_group + j / spatial_size,
+        )
+    }
+
+    fn lp_norms(&self, axis: usize, p: u32) -> Vec<DType> {
+        let axis_size = self.get_dim_size(axis);
+        let inner = get_size_from(self.get_sh(), axis + 1);
+        let outer = self.size / (axis_size * inner);
+
+        let mut norms = vec![zero(); outer * inner];
+        for o in 0..outer {
+            for i in 0..inner {
+                let mut norm: DType = zero();
+                for a in 0..axis_size {
+                    let x = self.get_ix((o * axis_size + a) * inner + i);
+                    norm = norm + if p == 1 { x.abs() } else { x * x };
+                }
+                norms[o * inner + i] = if p == 1 { norm } else { norm.sqrt() };
+            }
+        }
+        norms
+    }
+
+    // Divides by the L1 or L2 norm along the axis (ONNX LpNormalization).
+    // Vectors with norm 0 are mapped to 0
+    pub fn lp_normalize(&self, axis: usize, p: u32) -> Tensor<DType> {
+        if p != 1 && p != 2 {
+            panic!("Lp normalization only supports p = 1 or p = 2");
+        }
+
+        let axis_size = self.get_dim_size(axis);
+        let inner = get_size_from(self.get_sh(), axis + 1);
+        let norms = self.lp_norms(axis, p);
+
+        let mut values = vec![zero(); self.size];
+        for i in 0..self.size {
+            let norm = norms[(i / (axis_size * inner)) * inner + i % inner];
+            if norm != zero() {
+                values[i] = self.get_ix(i) / norm;
+            }
+        }
+
+        Tensor::new(
+            self.get_sh().to_vec(),
+            compute_strides(self.get_sh()),
+            self.size,
+            values,
+        )
+    }
+
+    pub fn lp_normalize_backward(
+        &self,
+        grad: &Tensor<DType>,
+        axis: usize,
+        p: u32,
+    ) -> Tensor<DType> {
+        if p != 1 && p != 2 {
+            panic!("Lp normalization only supports p = 1 or p = 2");
+        }
+
+        let axis_size = self.get_dim_size(axis);
+        let inner = get_size_from(self.get_sh(), axis + 1);
+        let outer = self.size / (axis_size * inner);
+        let norms = self.lp_norms(axis, p);
+
+        let mut values = vec![zero(); self.size];
+        for o in 0..outer {
+            for i in 0..inner {
+                let norm = norms[o * inner + i];
+                if norm == zero() {
+                    continue;
+                }
+
+                let mut dot = zero();
+                for a in 0..axis_size {
+                    let ix = (o * axis_size + a) * inner + i;
+                    dot = dot + grad.get_ix(ix) * self.get_ix(ix);
+                }
+
+                for a in 0..axis_size {
+                    let ix = (o * axis_size + a) * inner + i;
+                    let x = self.get_ix(ix);
+                    // Derivative of the norm with respect to x
+                    let dnorm = if p == 1 { x.signum() } else { x / norm };
+                    values[ix] = grad.get_ix(ix) / norm - dnorm * dot / (norm * norm);
+                }
+            }
+        }
+
+        Tensor::new(
+            self.get_sh().to_vec(),
+            compute_strides(self.get_sh()),
+            self.size,
+            values,
+        )
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: Float,
+    DType: FromPrimitive,
+{
+    // Mean and variance of every channel over the batch and spatial
+    // dimensions of a [N, C, ...] tensor, computed in two passes
+    fn channel_statistics(&self) -> (Vec<DType>, Vec<DType>) {
+        let channels = self.get_dim_size(1);
+        let spatial_size = get_size_from(self.get_sh(), 2);
+        let n = match DType::from_usize(self.size / channels) {
+            Some(n) => n,
+            None => panic!("Can not represent normalization size for given dtype"),
+        };
+
+        let mut mean = vec![zero(); channels];
+        for i in 0..self.size {
+            let c = (i / spatial_size) % channels;
+            mean[c] = mean[c] + self.get_ix(i);
+        }
+        for c in 0..channels {
+            mean[c] = mean[c] / n;
+        }
+
+        let mut variance = vec![zero(); channels];
+        for i in 0..self.size {
+            let c = (i / spatial_size) % channels;
+            let d = self.get_ix(i) - mean[c];
+            variance[c] = variance[c] + d * d;
+        }
+
+        for c in 0..channels {
+            variance[c] = variance[c] / n;
+        }
+
+        (mean, variance)
+    }
+
+    // BatchNormalization in training mode. Returns the output, the updated
+    // running mean and variance and the saved mean and inverse standard deviation.
+    // The running statistics are updated as running * momentum + batch * (1 - momentum)
+    pub fn batch_norm_train(
+        &self,
+        scale: &Tensor<DType>,
+        bias: &Tensor<DType>,
+        running_mean: &Tensor<DType>,
+        running_var: &Tensor<DType>,
+        momentum: DType,
+        epsilon: DType,
+    ) -> (
+        Tensor<DType>,
+        Tensor<DType>,
+        Tensor<DType>,
+        Tensor<DType>,
+        Tensor<DType>,
+    ) {
+        let channels = self.get_dim_size(1);
+        let spatial_size = get_size_from(self.get_sh(), 2);
+        let (mean, variance) = self.channel_statistics();
+
+        let mut inv_std = vec![zero(); channels];
+        for c in 0..channels {
+            inv_std[c] = (variance[c] + epsilon).sqrt().recip();
+        }
+
+        let mut values = vec![zero(); self.size];
+        for i in 0..self.size {
+            let c = (i / spatial_size) % channels;
+            values[i] = (self.get_ix(i) - mean[c]) * inv_std[c] * scale.get_ix(c) + bias.get_ix(c);
+        }
+
+        let one_minus_momentum = DType::one() - momentum;
+        let mut new_mean = vec![zero(); channels];
+        let mut new_var = vec![zero(); channels];
+        for c in 0..channels {
+            new_mean[c] = running_mean.get_ix(c) * momentum + mean[c] * one_minus_momentum;
+            new_var[c] = running_var.get_ix(c) * momentum + variance[c] * one_minus_momentum;
+        }
+
+        let stat_shape = vec![channels];
+        (
+            Tensor::new(
+                self.get_sh().to_vec(),
+                compute_strides(self.get_sh()),
+                self.size,
+                values,
+            ),
+            Tensor::new_from_shape(&stat_shape, &new_mean),
+            Tensor::new_from_shape(&stat_shape, &new_var),
+            Tensor::new_from_shape(&stat_shape, &mean),
+            Tensor::new_from_shape(&stat_shape, &inv_std),
+        )
+    }
+
+    // Returns the gradients for the input, the scale and the bias, using the
+    // mean and inverse standard deviation saved by batch_norm_train
+    pub fn batch_norm_backward(
+        &self,
+        grad: &Tensor<DType>,
+        scale: &Tensor<DType>,
+        saved_mean: &Tensor<DType>,
+        saved_inv_std: &Tensor<DType>,
+    ) -> (Tensor<DType>, Tensor<DType>, Tensor<DType>) {
+        let channels = self.get_dim_size(1);
+        let spatial_size = get_size_from(self.get_sh(), 2);
+        let n = DType::from_usize(self.size / channels).unwrap();
+
+        let mut scale_grad = vec![zero(); channels];
+        let mut bias_grad = vec![zero(); channels];
+        for i in 0..self.size {
+            let c = (i / spatial_size) % channels;
+            let g = grad.get_ix(i);
+            let normalized = (self.get_ix(i) - saved_mean.get_ix(c)) * saved_inv_std.get_ix(c);
+            scale_grad[c] = scale_grad[c] + g * normalized;
+            bias_grad[c] = bias_grad[c] + g;
+        }
+
+        let mut values = vec![zero(); self.size];
+        for i in 0..self.size {
+            let c = (i / spatial_size) % channels;
+            let inv_std = saved_inv_std.get_ix(c);
+            let normalized = (self.get_ix(i) - saved_mean.get_ix(c)) * inv_std;
+            values[i] = scale.get_ix(c) * inv_std / n
+                * (n * grad.get_ix(i) - bias_grad[c] - normalized * scale_grad[c]);
+        }
+
+        let stat_shape = vec![channels];
+        (
+            Tensor::new(
+                self.get_sh().to_vec(),
+                compute_strides(self.get_sh()),
+                self.size,
+                values,
+            ),
+            Tensor::new_from_shape(&stat_shape, &scale_grad),
+            Tensor::new_from_shape(&stat_shape, &bias_grad),
+        )
+    }
+}
@@ -0,0 +1,192 @@
+use crate::ops::activation::*;
+use crate::ops::conv::*;
+use crate::shape::*;
+use crate::tensor::*;
+use crate::utils::round_half_to_even;
+use js_sys::Uint32Array;
+use num_traits::Bounded;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: ToPrimitive,
+{
+    // Values minus their zero point in i32. zero_points has either one entry or
+    // one entry per index of axis 0, as for per channel quantized kernels
+    pub fn _subtract_zero_point(&self, zero_points: &Tensor<DType>) -> Tensor<i32> {
+        let size = get_size(self.get_sh());
+        let channels = self.get_dim_size(0);
+        let zero_point_count = get_size(zero_points.get_sh());
+        if zero_point_count != 1 && zero_point_count != channels {
+            panic!("Zero point needs either one entry or one entry per channel");
+        }
+        let channel_size = size / channels;
+
+        let mut values = vec![0; size];
+        for i in 0..size {
+            let zero_point = if zero_point_count == 1 {
+                zero_points.get_ix(0)
+            } else {
+                zero_points.get_ix(i / channel_size)
+            };
+            values[i] = self.get_ix(i).to_i32().unwrap() - zero_point.to_i32().unwrap();
+        }
+        Tensor::new_from_shape(self.get_sh(), &values)
+    }
+
+    // ONNX ConvInteger with an optional i32 bias, accumulating in i32.
+    // Padded positions contribute nothing, as if they held the zero point
+    pub fn _conv_integer<W>(
+        &self,
+        x_zero_point: DType,
+        kernel: &Tensor<W>,
+        w_zero_point: &Tensor<W>,
+        bias: Option<&Tensor<i32>>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<i32>
+    where
+        W: Copy,
+        W: ToPrimitive,
+    {
+        let x_zero_point = Tensor::new_from_shape(&vec![1], &vec![x_zero_point]);
+        let x = self._subtract_zero_point(&x_zero_point);
+        let w = kernel._subtract_zero_point(w_zero_point);
+
+        x._conv(&w, bias, dilations, group, pads, strides, Activation::None)
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: ToPrimitive,
+    DType: FromPrimitive,
+    DType: Bounded,
+{
+    // ONNX QLinearConv. w_scale and w_zero_point have either one entry or one entry
+    // per output channel, the output is quantized with y_scale and y_zero_point
+    pub fn _qlinear_conv<W>(
+        &self,
+        x_scale: f32,
+        x_zero_point: DType,
+        kernel: &Tensor<W>,
+        w_scale: &Tensor<f32>,
+        w_zero_point: &Tensor<W>,
+        y_scale: f32,
+        y_zero_point: DType,
+        bias: Option<&Tensor<i32>>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+    ) -> Tensor<DType>
+    where
+        W: Copy,
+        W: ToPrimitive,
+    {
+        let accumulated = self._conv_integer(
+            x_zero_point,
+            kernel,
+            w_zero_point,
+            bias,
+            dilations,
+            group,
+            pads,
+            strides,
+        );
+
+        let M = kernel.get_dim_size(0);
+        let scale_count = get_size(w_scale.get_sh());
+        if scale_count != 1 && scale_count != M {
+            panic!("Kernel scale needs either one entry or one entry per output channel");
+        }
+        let mut multipliers = vec![0.0; M];
+        for m in 0..M {
+            let w_scale = w_scale.get_ix(if scale_count == 1 { 0 } else { m });
+            multipliers[m] = x_scale * w_scale / y_scale;
+        }
+
+        let min = DType::min_value().to_f32().unwrap();
+        let max = DType::max_value().to_f32().unwrap();
+        let y_zero_point = y_zero_point.to_f32().unwrap();
+
+        let size = get_size(accumulated.get_sh());
+        let output_size = get_size_from(accumulated.get_sh(), 2);
+        let mut values = Vec::with_capacity(size);
+        for i in 0..size {
+            let m = (i / output_size) % M;
+            let y =
+                round_half_to_even(accumulated.get_ix(i) as f32 * multipliers[m]) + y_zero_point;
+            values.push(DType::from_f32(y.max(min).min(max)).unwrap());
+        }
+        Tensor::new_from_shape(accumulated.get_sh(), &values)
+    }
+
+    pub fn conv_integer<W>(
+        &self,
+        x_zero_point: DType,
+        kernel: &Tensor<W>,
+        w_zero_point: &Tensor<W>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<i32>
+    where
+        W: Copy,
+        W: ToPrimitive,
+    {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_integer(
+            x_zero_point,
+            kernel,
+            w_zero_point,
+            None,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+        );
+    }
+
+    pub fn qlinear_conv<W>(
+        &self,
+        x_scale: f32,
+        x_zero_point: DType,
+        kernel: &Tensor<W>,
+        w_scale: &Tensor<f32>,
+        w_zero_point: &Tensor<W>,
+        y_scale: f32,
+        y_zero_point: DType,
+        bias: Option<&Tensor<i32>>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+    ) -> Tensor<DType>
+    where
+        W: Copy,
+        W: ToPrimitive,
+    {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._qlinear_conv(
+            x_scale,
+            x_zero_point,
+            kernel,
+            w_scale,
+            w_zero_point,
+            y_scale,
+            y_zero_point,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+        );
+    }
+}
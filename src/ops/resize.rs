@@ -0,0 +1,363 @@
+use crate::shape::*;
+use crate::tensor::*;
+use js_sys::Float32Array;
+use js_sys::Uint32Array;
+use num_traits::zero;
+use num_traits::Bounded;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::ToPrimitive;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NearestMode {
+    RoundPreferFloor,
+    RoundPreferCeil,
+    Floor,
+    Ceil,
+}
+
+impl NearestMode {
+    // Mode: 0 == round_prefer_floor, 1 == round_prefer_ceil, 2 == floor, 3 == ceil
+    pub fn from_u32(mode: u32) -> NearestMode {
+        match mode {
+            0 => NearestMode::RoundPreferFloor,
+            1 => NearestMode::RoundPreferCeil,
+            2 => NearestMode::Floor,
+            3 => NearestMode::Ceil,
+            _ => panic!("Unknown nearest mode {}", mode),
+        }
+    }
+
+    pub fn index(&self, x: f64) -> i64 {
+        let result = match *self {
+            NearestMode::RoundPreferFloor => {
+                if x - x.floor() == 0.5 {
+                    x.floor()
+                } else {
+                    x.round()
+                }
+            }
+            NearestMode::RoundPreferCeil => {
+                if x - x.floor() == 0.5 {
+                    x.ceil()
+                } else {
+                    x.round()
+                }
+            }
+            NearestMode::Floor => x.floor(),
+            NearestMode::Ceil => x.ceil(),
+        };
+        result as i64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResizeMode {
+    Nearest(NearestMode),
+    Linear,
+    // Cubic coefficient a and whether weights of samples outside of the input are dropped
+    Cubic(f32, bool),
+}
+
+impl ResizeMode {
+    // Mode: 0 == nearest, 1 == linear, 2 == cubic
+    pub fn from_parameters(
+        mode: u32,
+        nearest_mode: u32,
+        cubic_coeff_a: f32,
+        exclude_outside: bool,
+    ) -> ResizeMode {
+        match mode {
+            0 => ResizeMode::Nearest(NearestMode::from_u32(nearest_mode)),
+            1 => ResizeMode::Linear,
+            2 => ResizeMode::Cubic(cubic_coeff_a, exclude_outside),
+            _ => panic!("Unknown resize mode {}", mode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateTransform {
+    HalfPixel,
+    AlignCorners,
+    Asymmetric,
+    PytorchHalfPixel,
+    TfCropAndResize,
+}
+
+impl CoordinateTransform {
+    // Mode: 0 == half_pixel, 1 == align_corners, 2 == asymmetric,
+    // 3 == pytorch_half_pixel, 4 == tf_crop_and_resize
+    pub fn from_u32(mode: u32) -> CoordinateTransform {
+        match mode {
+            0 => CoordinateTransform::HalfPixel,
+            1 => CoordinateTransform::AlignCorners,
+            2 => CoordinateTransform::Asymmetric,
+            3 => CoordinateTransform::PytorchHalfPixel,
+            4 => CoordinateTransform::TfCropAndResize,
+            _ => panic!("Unknown coordinate transformation mode {}", mode),
+        }
+    }
+
+    // Coordinate in the input of the output position x. roi_start and roi_end
+    // are only used by tf_crop_and_resize
+    pub fn to_input(
+        &self,
+        x: usize,
+        scale: f64,
+        in_size: usize,
+        out_size: usize,
+        roi_start: f64,
+        roi_end: f64,
+    ) -> f64 {
+        let x = x as f64;
+        match *self {
+            CoordinateTransform::HalfPixel => (x + 0.5) / scale - 0.5,
+            CoordinateTransform::AlignCorners => {
+                if out_size == 1 {
+                    0.
+                } else {
+                    x * (in_size - 1) as f64 / (out_size - 1) as f64
+                }
+            }
+            CoordinateTransform::Asymmetric => x / scale,
+            CoordinateTransform::PytorchHalfPixel => {
+                if out_size > 1 {
+                    (x + 0.5) / scale - 0.5
+                } else {
+                    0.
+                }
+            }
+            CoordinateTransform::TfCropAndResize => {
+                let length = (in_size - 1) as f64;
+                if out_size > 1 {
+                    roi_start * length + x * (roi_end - roi_start) * length / (out_size - 1) as f64
+                } else {
+                    0.5 * (roi_start + roi_end) * length
+                }
+            }
+        }
+    }
+}
+
+// Keys cubic convolution weights of the samples at floor(x) - 1 .. floor(x) + 2,
+// where ratio = x - floor(x)
+pub fn cubic_coefficients(ratio: f64, a: f64) -> [f64; 4] {
+    let far = |d: f64| ((a * d - 5. * a) * d + 8. * a) * d - 4. * a;
+    let near = |d: f64| ((a + 2.) * d - (a + 3.)) * d * d + 1.;
+    [
+        far(ratio + 1.),
+        near(ratio),
+        near(1. - ratio),
+        far(2. - ratio),
+    ]
+}
+
+// Input samples and their weights contributing to the coordinate x along an
+// axis of size in_size. Samples outside of the input are clamped to the edge
+pub fn interpolation_taps(x: f64, in_size: usize, mode: ResizeMode) -> Vec<(usize, f64)> {
+    let clamp = |i: i64| i.max(0).min(in_size as i64 - 1) as usize;
+    match mode {
+        ResizeMode::Nearest(nearest_mode) => vec![(clamp(nearest_mode.index(x)), 1.)],
+        ResizeMode::Linear => {
+            let x = x.max(0.).min((in_size - 1) as f64);
+            let low = x.floor() as i64;
+            let ratio = x - low as f64;
+            vec![(clamp(low), 1. - ratio), (clamp(low + 1), ratio)]
+        }
+        ResizeMode::Cubic(a, exclude_outside) => {
+            let low = x.floor() as i64;
+            let coefficients = cubic_coefficients(x - low as f64, a as f64);
+            let mut result = Vec::with_capacity(4);
+            let mut total = 0.;
+            for k in 0..4 {
+                let i = low - 1 + k as i64;
+                if exclude_outside && (i < 0 || i >= in_size as i64) {
+                    continue;
+                }
+                result.push((clamp(i), coefficients[k]));
+                total += coefficients[k];
+            }
+            if exclude_outside {
+                for k in 0..result.len() {
+                    result[k].1 /= total;
+                }
+            }
+            result
+        }
+    }
+}
+
+// Input samples of every output position along one axis and whether the position
+// lies outside of the roi. Without a size the output size is floor(in_size * scale)
+fn axis_taps(
+    in_size: usize,
+    size: Option<usize>,
+    scale: Option<f32>,
+    roi_start: f64,
+    roi_end: f64,
+    mode: ResizeMode,
+    coordinate_transform: CoordinateTransform,
+) -> (Vec<Vec<(usize, f64)>>, Vec<bool>) {
+    let (out_size, scale) = match size {
+        Some(size) => (size, size as f64 / in_size as f64),
+        None => {
+            let scale = scale.unwrap() as f64;
+            ((in_size as f64 * scale).floor() as usize, scale)
+        }
+    };
+
+    let mut taps = Vec::with_capacity(out_size);
+    let mut outside = vec![false; out_size];
+    for i in 0..out_size {
+        let x = coordinate_transform.to_input(i, scale, in_size, out_size, roi_start, roi_end);
+        if coordinate_transform == CoordinateTransform::TfCropAndResize
+            && (x < 0. || x > (in_size - 1) as f64)
+        {
+            outside[i] = true;
+        }
+        taps.push(interpolation_taps(x, in_size, mode));
+    }
+    (taps, outside)
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: ToPrimitive,
+    DType: Bounded,
+{
+    // ONNX Resize. Either sizes or scales has one entry per axis, the other one is empty.
+    // roi is [starts..., ends...] in normalized coordinates and only used by
+    // tf_crop_and_resize, where positions outside of the roi get extrapolation_value.
+    // The interpolation is computed in f64, integer dtypes round to the nearest value
+    pub fn _resize(
+        &self,
+        sizes: &Vec<usize>,
+        scales: &Vec<f32>,
+        roi: &Vec<f32>,
+        mode: ResizeMode,
+        coordinate_transform: CoordinateTransform,
+        extrapolation_value: DType,
+    ) -> Tensor<DType> {
+        let rank = self.rank();
+        if sizes.len() != rank && scales.len() != rank {
+            panic!("Resize requires either sizes or scales for every axis");
+        }
+
+        let mut shape = self.get_sh().clone();
+        let mut values: Vec<f64> = self
+            .get_values()
+            .iter()
+            .map(|x| x.to_f64().unwrap())
+            .collect();
+        let mut outside = vec![vec![]; rank];
+
+        for axis in 0..rank {
+            let (roi_start, roi_end) = if roi.len() == 2 * rank {
+                (roi[axis] as f64, roi[rank + axis] as f64)
+            } else {
+                (0., 1.)
+            };
+            let (taps, axis_outside) = axis_taps(
+                shape[axis],
+                sizes.get(axis).copied(),
+                scales.get(axis).copied(),
+                roi_start,
+                roi_end,
+                mode,
+                coordinate_transform,
+            );
+            outside[axis] = axis_outside;
+
+            let identity = taps.len() == shape[axis]
+                && (0..taps.len()).all(|i| taps[i].iter().all(|t| t.0 == i || t.1 == 0.));
+            if identity {
+                continue;
+            }
+
+            let outer: usize = shape[..axis].iter().product();
+            let inner: usize = shape[axis + 1..].iter().product();
+            let in_size = shape[axis];
+            let out_size = taps.len();
+            let mut result = vec![0.; outer * out_size * inner];
+            for o in 0..outer {
+                for i in 0..out_size {
+                    let out_base = (o * out_size + i) * inner;
+                    for &(ix, weight) in taps[i].iter() {
+                        let in_base = (o * in_size + ix) * inner;
+                        for j in 0..inner {
+                            result[out_base + j] += weight * values[in_base + j];
+                        }
+                    }
+                }
+            }
+            values = result;
+            shape[axis] = out_size;
+        }
+
+        let integer = DType::from_f64(0.5).unwrap() == zero();
+        let min = DType::min_value().to_f64().unwrap();
+        let max = DType::max_value().to_f64().unwrap();
+
+        let strides = compute_strides(&shape);
+        let size = get_size(&shape);
+        let mut result = Vec::with_capacity(size);
+        let mut index = vec![0; rank];
+        for i in 0..size {
+            if (0..rank).any(|axis| outside[axis].len() > 0 && outside[axis][index[axis]]) {
+                result.push(extrapolation_value);
+            } else {
+                let mut value = values[i];
+                if integer {
+                    value = value.round();
+                }
+                result.push(DType::from_f64(value.max(min).min(max)).unwrap());
+            }
+            increment_index(&mut index, &shape);
+        }
+
+        Tensor::new(shape, strides, size, result)
+    }
+
+    // Mode: 0 == nearest, 1 == linear, 2 == cubic
+    // Nearest mode: 0 == round_prefer_floor, 1 == round_prefer_ceil, 2 == floor, 3 == ceil
+    // Coordinate transform: 0 == half_pixel, 1 == align_corners, 2 == asymmetric,
+    // 3 == pytorch_half_pixel, 4 == tf_crop_and_resize
+    pub fn resize(
+        &self,
+        sizes: Uint32Array,
+        scales: Float32Array,
+        roi: Float32Array,
+        mode: u32,
+        coordinate_transform: u32,
+        nearest_mode: u32,
+        cubic_coeff_a: f32,
+        exclude_outside: bool,
+        extrapolation_value: DType,
+    ) -> Tensor<DType> {
+        let mut _sizes: Vec<usize> = vec![0; sizes.length() as usize];
+        for i in 0..sizes.length() {
+            _sizes[i as usize] = sizes.get_index(i) as usize;
+        }
+        let mut _scales: Vec<f32> = vec![0.0; scales.length() as usize];
+        for i in 0..scales.length() {
+            _scales[i as usize] = scales.get_index(i);
+        }
+        let mut _roi: Vec<f32> = vec![0.0; roi.length() as usize];
+        for i in 0..roi.length() {
+            _roi[i as usize] = roi.get_index(i);
+        }
+
+        return self._resize(
+            &_sizes,
+            &_scales,
+            &_roi,
+            ResizeMode::from_parameters(mode, nearest_mode, cubic_coeff_a, exclude_outside),
+            CoordinateTransform::from_u32(coordinate_transform),
+            extrapolation_value,
+        );
+    }
+}
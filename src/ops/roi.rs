@@ -0,0 +1,308 @@
+use crate::shape::*;
+use crate::tensor::*;
+use num_traits::zero;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoiPoolMode {
+    Average,
+    Max,
+}
+
+impl RoiPoolMode {
+    // Mode: 0 == avg, 1 == max
+    pub fn from_u32(mode: u32) -> RoiPoolMode {
+        match mode {
+            0 => RoiPoolMode::Average,
+            1 => RoiPoolMode::Max,
+            _ => panic!("Unknown roi pool mode {}", mode),
+        }
+    }
+}
+
+// Bilinear interpolation weights and flat positions of the four neighbours of (y, x)
+// in a height x width plane. Samples more than one pixel outside get zero weights
+fn bilinear_neighbours<DType>(
+    y: DType,
+    x: DType,
+    height: usize,
+    width: usize,
+) -> [(usize, DType); 4]
+where
+    DType: Float,
+    DType: FromPrimitive,
+{
+    let one: DType = DType::one();
+    if y < -one
+        || y > DType::from_usize(height).unwrap()
+        || x < -one
+        || x > DType::from_usize(width).unwrap()
+    {
+        return [(0, zero()); 4];
+    }
+
+    let mut y = y.max(zero());
+    let mut x = x.max(zero());
+    let mut y_low = y.floor().to_usize().unwrap();
+    let mut x_low = x.floor().to_usize().unwrap();
+    let y_high;
+    let x_high;
+    if y_low >= height - 1 {
+        y_low = height - 1;
+        y_high = height - 1;
+        y = DType::from_usize(y_low).unwrap();
+    } else {
+        y_high = y_low + 1;
+    }
+    if x_low >= width - 1 {
+        x_low = width - 1;
+        x_high = width - 1;
+        x = DType::from_usize(x_low).unwrap();
+    } else {
+        x_high = x_low + 1;
+    }
+
+    let ly = y - DType::from_usize(y_low).unwrap();
+    let lx = x - DType::from_usize(x_low).unwrap();
+    let hy = one - ly;
+    let hx = one - lx;
+    [
+        (y_low * width + x_low, hy * hx),
+        (y_low * width + x_high, hy * lx),
+        (y_high * width + x_low, ly * hx),
+        (y_high * width + x_high, ly * lx),
+    ]
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Float,
+    DType: FromPrimitive,
+{
+    // ONNX RoiAlign on an input of shape [N, C, H, W]. rois has shape [num_rois, 4]
+    // with (x1, y1, x2, y2) in input coordinates before spatial_scale. A sampling_ratio
+    // of 0 samples ceil(roi_size / output_size) points per bin. Without half_pixel the
+    // legacy output_half_pixel transform is used, where rois are at least one pixel wide.
+    // The max mode takes the maximum of the weighted neighbours as in onnxruntime
+    pub fn _roi_align(
+        &self,
+        rois: &Tensor<DType>,
+        batch_indices: &Tensor<u32>,
+        output_height: usize,
+        output_width: usize,
+        sampling_ratio: usize,
+        spatial_scale: DType,
+        mode: RoiPoolMode,
+        half_pixel: bool,
+    ) -> Tensor<DType> {
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        let num_rois = rois.get_dim_size(0);
+
+        let one: DType = DType::one();
+        let half = DType::from_f32(0.5).unwrap();
+        let offset = if half_pixel { half } else { zero() };
+
+        let output_shape = vec![num_rois, C, output_height, output_width];
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        for r in 0..num_rois {
+            let n = batch_indices.get_ix(r) as usize;
+            let start_w = rois.get_ix(r * 4) * spatial_scale - offset;
+            let start_h = rois.get_ix(r * 4 + 1) * spatial_scale - offset;
+            let end_w = rois.get_ix(r * 4 + 2) * spatial_scale - offset;
+            let end_h = rois.get_ix(r * 4 + 3) * spatial_scale - offset;
+
+            let mut roi_width = end_w - start_w;
+            let mut roi_height = end_h - start_h;
+            if !half_pixel {
+                roi_width = roi_width.max(one);
+                roi_height = roi_height.max(one);
+            }
+
+            let bin_height = roi_height / DType::from_usize(output_height).unwrap();
+            let bin_width = roi_width / DType::from_usize(output_width).unwrap();
+            let grid_height = if sampling_ratio > 0 {
+                sampling_ratio
+            } else {
+                bin_height.ceil().max(zero()).to_usize().unwrap()
+            };
+            let grid_width = if sampling_ratio > 0 {
+                sampling_ratio
+            } else {
+                bin_width.ceil().max(zero()).to_usize().unwrap()
+            };
+            let count = DType::from_usize((grid_height * grid_width).max(1)).unwrap();
+            let grid_height_d = DType::from_usize(grid_height).unwrap();
+            let grid_width_d = DType::from_usize(grid_width).unwrap();
+
+            // Neighbours of all sample points of every bin, the same for every channel
+            let mut samples = Vec::with_capacity(output_height * output_width);
+            for ph in 0..output_height {
+                for pw in 0..output_width {
+                    let mut bin_samples = Vec::with_capacity(grid_height * grid_width);
+                    for iy in 0..grid_height {
+                        let y = start_h
+                            + DType::from_usize(ph).unwrap() * bin_height
+                            + (DType::from_usize(iy).unwrap() + half) * bin_height / grid_height_d;
+                        for ix in 0..grid_width {
+                            let x = start_w
+                                + DType::from_usize(pw).unwrap() * bin_width
+                                + (DType::from_usize(ix).unwrap() + half) * bin_width
+                                    / grid_width_d;
+                            bin_samples.push(bilinear_neighbours(y, x, H, W));
+                        }
+                    }
+                    samples.push(bin_samples);
+                }
+            }
+
+            for c in 0..C {
+                let input_base = (n * C + c) * H * W;
+                let output_base = (r * C + c) * output_height * output_width;
+                for bin in 0..samples.len() {
+                    let mut result: DType = zero();
+                    for (s, neighbours) in samples[bin].iter().enumerate() {
+                        match mode {
+                            RoiPoolMode::Average => {
+                                for &(position, weight) in neighbours.iter() {
+                                    result = result + weight * self.get_ix(input_base + position);
+                                }
+                            }
+                            RoiPoolMode::Max => {
+                                let mut value =
+                                    neighbours[0].1 * self.get_ix(input_base + neighbours[0].0);
+                                for &(position, weight) in neighbours[1..].iter() {
+                                    value = value.max(weight * self.get_ix(input_base + position));
+                                }
+                                result = if s == 0 { value } else { result.max(value) };
+                            }
+                        }
+                    }
+                    if mode == RoiPoolMode::Average {
+                        result = result / count;
+                    }
+                    values[output_base + bin] = result;
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // ONNX MaxRoiPool on an input of shape [N, C, H, W]. rois has shape [num_rois, 5]
+    // with (batch_index, x1, y1, x2, y2), empty bins produce 0
+    pub fn _max_roi_pool(
+        &self,
+        rois: &Tensor<DType>,
+        pooled_height: usize,
+        pooled_width: usize,
+        spatial_scale: DType,
+    ) -> Tensor<DType> {
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        let num_rois = rois.get_dim_size(0);
+
+        let output_shape = vec![num_rois, C, pooled_height, pooled_width];
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        let clamp = |x: i64, size: usize| x.max(0).min(size as i64) as usize;
+
+        for r in 0..num_rois {
+            let n = rois.get_ix(r * 5).to_usize().unwrap();
+            let start_w = (rois.get_ix(r * 5 + 1) * spatial_scale)
+                .round()
+                .to_i64()
+                .unwrap();
+            let start_h = (rois.get_ix(r * 5 + 2) * spatial_scale)
+                .round()
+                .to_i64()
+                .unwrap();
+            let end_w = (rois.get_ix(r * 5 + 3) * spatial_scale)
+                .round()
+                .to_i64()
+                .unwrap();
+            let end_h = (rois.get_ix(r * 5 + 4) * spatial_scale)
+                .round()
+                .to_i64()
+                .unwrap();
+
+            let roi_height = (end_h - start_h + 1).max(1) as f64;
+            let roi_width = (end_w - start_w + 1).max(1) as f64;
+            let bin_height = roi_height / pooled_height as f64;
+            let bin_width = roi_width / pooled_width as f64;
+
+            for ph in 0..pooled_height {
+                let h_start = clamp((ph as f64 * bin_height).floor() as i64 + start_h, H);
+                let h_end = clamp(((ph + 1) as f64 * bin_height).ceil() as i64 + start_h, H);
+                for pw in 0..pooled_width {
+                    let w_start = clamp((pw as f64 * bin_width).floor() as i64 + start_w, W);
+                    let w_end = clamp(((pw + 1) as f64 * bin_width).ceil() as i64 + start_w, W);
+                    if h_end <= h_start || w_end <= w_start {
+                        continue;
+                    }
+
+                    for c in 0..C {
+                        let input_base = (n * C + c) * H * W;
+                        let mut result = self.get_ix(input_base + h_start * W + w_start);
+                        for h in h_start..h_end {
+                            for w in w_start..w_end {
+                                result = result.max(self.get_ix(input_base + h * W + w));
+                            }
+                        }
+                        values[((r * C + c) * pooled_height + ph) * pooled_width + pw] = result;
+                    }
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // Mode: 0 == avg, 1 == max
+    pub fn roi_align(
+        &self,
+        rois: &Tensor<DType>,
+        batch_indices: &Tensor<u32>,
+        output_height: u32,
+        output_width: u32,
+        sampling_ratio: u32,
+        spatial_scale: DType,
+        mode: u32,
+        half_pixel: bool,
+    ) -> Tensor<DType> {
+        self._roi_align(
+            rois,
+            batch_indices,
+            output_height as usize,
+            output_width as usize,
+            sampling_ratio as usize,
+            spatial_scale,
+            RoiPoolMode::from_u32(mode),
+            half_pixel,
+        )
+    }
+
+    pub fn max_roi_pool(
+        &self,
+        rois: &Tensor<DType>,
+        pooled_height: u32,
+        pooled_width: u32,
+        spatial_scale: DType,
+    ) -> Tensor<DType> {
+        self._max_roi_pool(
+            rois,
+            pooled_height as usize,
+            pooled_width as usize,
+            spatial_scale,
+        )
+    }
+}
@@ -0,0 +1,251 @@
+use crate::ops::accumulate::*;
+use crate::shape::*;
+use crate::tensor::*;
+use num_traits::zero;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: FromPrimitive,
+{
+    // Reduces the rows (first axis) of the tensor into num_segments groups,
+    // every segment id has to be below num_segments. Empty segments are zero
+    pub fn aggregate_segments<F, F2, F3>(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+        op: F,
+        init: bool,
+        init_op: F2,
+        post: bool,
+        post_op: F3,
+    ) -> Tensor<DType>
+    where
+        F: Fn(DType, DType) -> DType,
+        F2: Fn(DType) -> DType,
+        F3: Fn(DType, usize) -> DType,
+    {
+        let rows = self.get_dim_size(0);
+        if segment_ids.size != rows {
+            panic!("Number of segment ids has to match the first dimension of the tensor");
+        }
+
+        let row_size = get_size_from(self.get_sh(), 1);
+
+        let mut result_shape = self.get_sh().to_vec();
+        result_shape[0] = num_segments;
+        let result_strides = compute_strides(&result_shape);
+        let result_size = num_segments * row_size;
+
+        let mut values: Vec<DType> = vec![zero(); result_size];
+        let mut count = vec![0; num_segments];
+
+        for i in 0..rows {
+            let segment = segment_ids.get_ix(i) as usize;
+            if segment >= num_segments {
+                panic!(
+                    "Segment id {} out of range for {} segments",
+                    segment, num_segments
+                );
+            }
+
+            let in_base = i * row_size;
+            let out_base = segment * row_size;
+            for j in 0..row_size {
+                let v = self.get_ix(in_base + j);
+                if init && count[segment] == 0 {
+                    values[out_base + j] = init_op(v);
+                } else {
+                    values[out_base + j] = op(values[out_base + j], v);
+                }
+            }
+
+            count[segment] += 1;
+        }
+
+        if post {
+            for i in 0..num_segments {
+                if count[i] == 0 {
+                    continue;
+                }
+                for j in 0..row_size {
+                    values[i * row_size + j] = post_op(values[i * row_size + j], count[i]);
+                }
+            }
+        }
+
+        Tensor::new(result_shape, result_strides, result_size, values)
+    }
+
+    // Number of segments for sorted segment ids, which is the last id + 1
+    pub fn sorted_num_segments(segment_ids: &Tensor<u32>) -> usize {
+        for i in 1..segment_ids.size {
+            if segment_ids.get_ix(i) < segment_ids.get_ix(i - 1) {
+                panic!("Segment ids have to be sorted");
+            }
+        }
+
+        if segment_ids.size == 0 {
+            return 0;
+        }
+        segment_ids.get_ix(segment_ids.size - 1) as usize + 1
+    }
+
+    pub fn unsorted_segment_sum(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        self.aggregate_segments(
+            segment_ids,
+            num_segments,
+            |a: DType, b: DType| a + b,
+            false,
+            |a: DType| a,
+            false,
+            |a: DType, _b: usize| a,
+        )
+    }
+
+    pub fn unsorted_segment_mean(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        self.aggregate_segments(
+            segment_ids,
+            num_segments,
+            |a: DType, b: DType| a + b,
+            false,
+            |a: DType| a,
+            true,
+            |a: DType, b: usize| {
+                a / DType::from_usize(b).expect(
+                    "Error in segment mean: Data type can not represent number of items in segment",
+                )
+            },
+        )
+    }
+
+    pub fn unsorted_segment_max(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        self.aggregate_segments(
+            segment_ids,
+            num_segments,
+            |a: DType, b: DType| if a > b { a } else { b },
+            true,
+            |a: DType| a,
+            false,
+            |a: DType, _b: usize| a,
+        )
+    }
+
+    pub fn unsorted_segment_min(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        self.aggregate_segments(
+            segment_ids,
+            num_segments,
+            |a: DType, b: DType| if a < b { a } else { b },
+            true,
+            |a: DType| a,
+            false,
+            |a: DType, _b: usize| a,
+        )
+    }
+
+    pub fn segment_sum(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_sum(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+
+    pub fn segment_mean(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_mean(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+
+    pub fn segment_max(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_max(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+
+    pub fn segment_min(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_min(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: FromPrimitive,
+    DType: Widen,
+{
+    pub fn unsorted_segment_sum_widened(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        Tensor::_narrow(
+            &self
+                ._widen()
+                .unsorted_segment_sum(segment_ids, num_segments),
+        )
+    }
+
+    // Means are rounded half away from zero as in the other widened means
+    pub fn unsorted_segment_mean_widened(
+        &self,
+        segment_ids: &Tensor<u32>,
+        num_segments: usize,
+    ) -> Tensor<DType> {
+        let means = self._widen().aggregate_segments(
+            segment_ids,
+            num_segments,
+            |a: DType::Wide, b: DType::Wide| a + b,
+            false,
+            |a: DType::Wide| a,
+            true,
+            |a: DType::Wide, b: usize| match DType::Wide::from_usize(b) {
+                Some(count) => divide_rounded(a, count),
+                None => panic!(
+                    "Error in segment mean: Data type can not represent number of items in segment"
+                ),
+            },
+        );
+        Tensor::_narrow(&means)
+    }
+
+    pub fn segment_sum_widened(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_sum_widened(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+
+    pub fn segment_mean_widened(&self, segment_ids: &Tensor<u32>) -> Tensor<DType> {
+        self.unsorted_segment_mean_widened(
+            segment_ids,
+            Tensor::<DType>::sorted_num_segments(segment_ids),
+        )
+    }
+}
@@ -0,0 +1,310 @@
+use crate::ops::resize::cubic_coefficients;
+use crate::shape::*;
+use crate::tensor::*;
+use crate::utils::round_half_to_even;
+use js_sys::Uint32Array;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridSampleMode {
+    Bilinear,
+    Nearest,
+    Bicubic,
+}
+
+impl GridSampleMode {
+    // Mode: 0 == bilinear, 1 == nearest, 2 == bicubic
+    pub fn from_u32(mode: u32) -> GridSampleMode {
+        match mode {
+            0 => GridSampleMode::Bilinear,
+            1 => GridSampleMode::Nearest,
+            2 => GridSampleMode::Bicubic,
+            _ => panic!("Unknown grid sample mode {}", mode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridPaddingMode {
+    Zeros,
+    Border,
+    Reflection,
+}
+
+impl GridPaddingMode {
+    // Mode: 0 == zeros, 1 == border, 2 == reflection
+    pub fn from_u32(mode: u32) -> GridPaddingMode {
+        match mode {
+            0 => GridPaddingMode::Zeros,
+            1 => GridPaddingMode::Border,
+            2 => GridPaddingMode::Reflection,
+            _ => panic!("Unknown grid padding mode {}", mode),
+        }
+    }
+}
+
+// Position in an axis of the given size for the normalized coordinate x in [-1, 1]
+fn denormalize(x: f64, size: usize, align_corners: bool) -> f64 {
+    if align_corners {
+        (x + 1.) / 2. * (size - 1) as f64
+    } else {
+        ((x + 1.) * size as f64 - 1.) / 2.
+    }
+}
+
+// Normalized coordinate in [-1, 1] of the position i in an axis of the given size
+fn normalize(i: usize, size: usize, align_corners: bool) -> f64 {
+    if align_corners {
+        if size == 1 {
+            0.
+        } else {
+            2. * i as f64 / (size - 1) as f64 - 1.
+        }
+    } else {
+        (2. * i as f64 + 1.) / size as f64 - 1.
+    }
+}
+
+// Reflects x at min and max until it lies between them
+fn reflect(x: f64, min: f64, max: f64) -> f64 {
+    let range = max - min;
+    if range <= 0. {
+        return min;
+    }
+    if x < min {
+        let distance = min - x;
+        let n = (distance / range).floor();
+        let rest = distance - n * range;
+        if n % 2. == 0. {
+            min + rest
+        } else {
+            max - rest
+        }
+    } else if x > max {
+        let distance = x - max;
+        let n = (distance / range).floor();
+        let rest = distance - n * range;
+        if n % 2. == 0. {
+            max - rest
+        } else {
+            min + rest
+        }
+    } else {
+        x
+    }
+}
+
+// Samples of one spatial axis between the sampling borders min and max
+struct GridAxis {
+    size: usize,
+    padding_mode: GridPaddingMode,
+    min: f64,
+    max: f64,
+}
+
+impl GridAxis {
+    fn new(size: usize, padding_mode: GridPaddingMode, align_corners: bool) -> GridAxis {
+        let (min, max) = if align_corners {
+            (0., (size - 1) as f64)
+        } else {
+            (-0.5, size as f64 - 0.5)
+        };
+        GridAxis {
+            size,
+            padding_mode,
+            min,
+            max,
+        }
+    }
+
+    fn contains(&self, x: f64) -> bool {
+        x >= self.min && x <= self.max
+    }
+
+    // x clamped or reflected back inside the sampling border for border and reflection padding
+    fn pad(&self, x: f64) -> f64 {
+        match self.padding_mode {
+            GridPaddingMode::Zeros => x,
+            GridPaddingMode::Border => x.max(0.).min((self.size - 1) as f64),
+            GridPaddingMode::Reflection => reflect(x, self.min, self.max),
+        }
+    }
+
+    // Index of the sample at i, None if it is padded with zero
+    fn index(&self, i: i64) -> Option<usize> {
+        let last = self.size as i64 - 1;
+        match self.padding_mode {
+            GridPaddingMode::Zeros => {
+                if i >= 0 && i <= last {
+                    Some(i as usize)
+                } else {
+                    None
+                }
+            }
+            GridPaddingMode::Border => Some(i.max(0).min(last) as usize),
+            GridPaddingMode::Reflection => {
+                let x = reflect(i as f64, self.min, self.max) as i64;
+                Some(x.max(0).min(last) as usize)
+            }
+        }
+    }
+
+    // Samples and their weights for the position x, already rounded for nearest
+    fn taps(&self, x: f64, mode: GridSampleMode) -> Vec<(Option<usize>, f64)> {
+        match mode {
+            GridSampleMode::Nearest => vec![(self.index(x as i64), 1.)],
+            GridSampleMode::Bilinear => {
+                let low = x.floor();
+                let ratio = x - low;
+                vec![
+                    (self.index(low as i64), 1. - ratio),
+                    (self.index(low as i64 + 1), ratio),
+                ]
+            }
+            GridSampleMode::Bicubic => {
+                let low = x.floor();
+                let coefficients = cubic_coefficients(x - low, -0.75);
+                (0..4)
+                    .map(|k| (self.index(low as i64 - 1 + k as i64), coefficients[k]))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Float,
+    DType: FromPrimitive,
+{
+    // ONNX GridSample on an input of shape [N, C, H, W] with a grid of shape
+    // [N, H_out, W_out, 2] holding (x, y) in normalized coordinates in [-1, 1].
+    // Bicubic uses the cubic coefficient -0.75. Computed in f64
+    pub fn _grid_sample(
+        &self,
+        grid: &Tensor<DType>,
+        mode: GridSampleMode,
+        padding_mode: GridPaddingMode,
+        align_corners: bool,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        let OH = grid.get_dim_size(1);
+        let OW = grid.get_dim_size(2);
+        if grid.get_dim_size(0) != N || grid.get_dim_size(3) != 2 {
+            panic!("Grid of grid sample needs shape [N, H_out, W_out, 2]");
+        }
+
+        let y_axis = GridAxis::new(H, padding_mode, align_corners);
+        let x_axis = GridAxis::new(W, padding_mode, align_corners);
+
+        let output_shape = vec![N, C, OH, OW];
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = vec![DType::zero(); o_size];
+
+        for n in 0..N {
+            for i in 0..OH * OW {
+                let grid_base = (n * OH * OW + i) * 2;
+                let mut x = denormalize(grid.get_ix(grid_base).to_f64().unwrap(), W, align_corners);
+                let mut y = denormalize(
+                    grid.get_ix(grid_base + 1).to_f64().unwrap(),
+                    H,
+                    align_corners,
+                );
+                // As in onnxruntime nearest rounds before padding, and both coordinates
+                // are padded when either of them lies outside of the sampling border
+                if mode == GridSampleMode::Nearest {
+                    x = round_half_to_even(x);
+                    y = round_half_to_even(y);
+                }
+                if !x_axis.contains(x) || !y_axis.contains(y) {
+                    x = x_axis.pad(x);
+                    y = y_axis.pad(y);
+                }
+                let y_taps = y_axis.taps(y, mode);
+                let x_taps = x_axis.taps(x, mode);
+
+                for c in 0..C {
+                    let input_base = (n * C + c) * H * W;
+                    let mut result = 0.;
+                    for &(h, y_weight) in y_taps.iter() {
+                        for &(w, x_weight) in x_taps.iter() {
+                            if let (Some(h), Some(w)) = (h, w) {
+                                let value = self.get_ix(input_base + h * W + w).to_f64().unwrap();
+                                result += y_weight * x_weight * value;
+                            }
+                        }
+                    }
+                    values[(n * C + c) * OH * OW + i] = DType::from_f64(result).unwrap();
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // ONNX AffineGrid for 2D, called on theta of shape [N, 2, 3]. size is [N, C, H, W],
+    // the grid has shape [N, H, W, 2] and holds (x, y) in normalized coordinates
+    pub fn _affine_grid(&self, size: &Vec<usize>, align_corners: bool) -> Tensor<DType> {
+        let N = size[0];
+        let H = size[2];
+        let W = size[3];
+        if self.get_dim_size(0) != N || self.get_dim_size(1) != 2 || self.get_dim_size(2) != 3 {
+            panic!("Theta of affine grid needs shape [N, 2, 3]");
+        }
+
+        let output_shape = vec![N, H, W, 2];
+        let output_strides = compute_strides(&output_shape);
+        let o_size = get_size(&output_shape);
+        let mut values = Vec::with_capacity(o_size);
+
+        for n in 0..N {
+            let theta: Vec<f64> = (0..6)
+                .map(|i| self.get_ix(n * 6 + i).to_f64().unwrap())
+                .collect();
+            for h in 0..H {
+                let y = normalize(h, H, align_corners);
+                for w in 0..W {
+                    let x = normalize(w, W, align_corners);
+                    for row in 0..2 {
+                        let value =
+                            theta[row * 3] * x + theta[row * 3 + 1] * y + theta[row * 3 + 2];
+                        values.push(DType::from_f64(value).unwrap());
+                    }
+                }
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // Mode: 0 == bilinear, 1 == nearest, 2 == bicubic
+    // Padding mode: 0 == zeros, 1 == border, 2 == reflection
+    pub fn grid_sample(
+        &self,
+        grid: &Tensor<DType>,
+        mode: u32,
+        padding_mode: u32,
+        align_corners: bool,
+    ) -> Tensor<DType> {
+        self._grid_sample(
+            grid,
+            GridSampleMode::from_u32(mode),
+            GridPaddingMode::from_u32(padding_mode),
+            align_corners,
+        )
+    }
+
+    pub fn affine_grid(&self, size: Uint32Array, align_corners: bool) -> Tensor<DType> {
+        let mut _size: Vec<usize> = vec![0; size.length() as usize];
+        for i in 0..size.length() {
+            _size[i as usize] = size.get_index(i) as usize;
+        }
+        self._affine_grid(&_size, align_corners)
+    }
+}
@@ -0,0 +1,243 @@
+use crate::shape::*;
+use crate::tensor::*;
+use num_traits::one;
+use num_traits::zero;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+use num_traits::ToPrimitive;
+use std::cmp::Ordering;
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+{
+    fn compare_rows(&self, a: usize, b: usize, row_size: usize) -> Ordering {
+        for j in 0..row_size {
+            let x = self.get_ix(a * row_size + j);
+            let y = self.get_ix(b * row_size + j);
+            match x.partial_cmp(&y) {
+                Some(Ordering::Equal) | None => {}
+                Some(ordering) => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+
+    // Unique slices along the given axis, or unique elements of the flattened
+    // tensor if no axis is given (ONNX Unique).
+    // Returns the unique values, the index of the first occurrence of every
+    // unique value, the inverse indices and the counts.
+    pub fn _unique(
+        &self,
+        axis: Option<usize>,
+        sorted: bool,
+    ) -> (Tensor<DType>, Tensor<u32>, Tensor<u32>, Tensor<u32>) {
+        let (rows, moved) = match axis {
+            None => (self._reshape(&vec![self.size]), 0),
+            Some(ax) => {
+                let mut permutation = vec![ax];
+                for i in 0..self.rank() {
+                    if i != ax {
+                        permutation.push(i);
+                    }
+                }
+                (self._transpose(&permutation), ax)
+            }
+        };
+
+        let num_rows = rows.get_dim_size(0);
+        let row_size = get_size_from(rows.get_sh(), 1);
+
+        let mut order: Vec<usize> = (0..num_rows).collect();
+        // Stable, so the first row of every group is its first occurrence
+        order.sort_by(|&a, &b| rows.compare_rows(a, b, row_size));
+
+        let mut first_occurrences = Vec::new();
+        let mut counts = Vec::new();
+        let mut group_of_row = vec![0; num_rows];
+        for i in 0..num_rows {
+            if i == 0 || rows.compare_rows(order[i - 1], order[i], row_size) != Ordering::Equal {
+                first_occurrences.push(order[i]);
+                counts.push(0);
+            }
+            group_of_row[order[i]] = counts.len() - 1;
+            let last = counts.len() - 1;
+            counts[last] += 1;
+        }
+
+        let num_unique = counts.len();
+        let mut group_order: Vec<usize> = (0..num_unique).collect();
+        if !sorted {
+            group_order.sort_by_key(|&g| first_occurrences[g]);
+        }
+        let mut output_position = vec![0; num_unique];
+        for i in 0..num_unique {
+            output_position[group_order[i]] = i;
+        }
+
+        let mut values = Vec::with_capacity(num_unique * row_size);
+        let mut indices = vec![0; num_unique];
+        let mut unique_counts = vec![0; num_unique];
+        for i in 0..num_unique {
+            let g = group_order[i];
+            let row = first_occurrences[g];
+            for j in 0..row_size {
+                values.push(rows.get_ix(row * row_size + j));
+            }
+            indices[i] = row as u32;
+            unique_counts[i] = counts[g] as u32;
+        }
+
+        let mut inverse = vec![0; num_rows];
+        for i in 0..num_rows {
+            inverse[i] = output_position[group_of_row[i]] as u32;
+        }
+
+        let mut unique_shape = rows.get_sh().to_vec();
+        unique_shape[0] = num_unique;
+        let mut unique = Tensor::new_from_shape(&unique_shape, &values);
+        if axis.is_some() {
+            let mut permutation = vec![0; self.rank()];
+            for i in 0..self.rank() {
+                if i < moved {
+                    permutation[i] = i + 1;
+                } else if i > moved {
+                    permutation[i] = i;
+                }
+            }
+            unique = unique._transpose(&permutation);
+        }
+
+        (
+            unique,
+            Tensor::new_from_shape(&vec![num_unique], &indices),
+            Tensor::new_from_shape(&vec![num_rows], &inverse),
+            Tensor::new_from_shape(&vec![num_unique], &unique_counts),
+        )
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: ToPrimitive,
+{
+    // Counts occurrences of every non negative integer in a 1D tensor,
+    // optionally summing weights instead of ones
+    pub fn _bincount<W>(&self, weights: Option<&Tensor<W>>, minlength: usize) -> Tensor<W>
+    where
+        W: Copy,
+        W: Num,
+    {
+        let mut length = minlength;
+        let mut bins = vec![0; self.size];
+        for i in 0..self.size {
+            match self.get_ix(i).to_usize() {
+                Some(bin) => {
+                    bins[i] = bin;
+                    if bin + 1 > length {
+                        length = bin + 1;
+                    }
+                }
+                None => panic!("Bincount is only defined for non negative integers"),
+            }
+        }
+
+        if let Some(w) = weights {
+            if w.size != self.size {
+                panic!(
+                    "Bincount needs one weight per value, got {} weights for {} values",
+                    w.size, self.size
+                );
+            }
+        }
+
+        let mut values: Vec<W> = vec![zero(); length];
+        for i in 0..self.size {
+            let weight = match weights {
+                None => one(),
+                Some(w) => w.get_ix(i),
+            };
+            values[bins[i]] = values[bins[i]] + weight;
+        }
+
+        Tensor::new_from_shape(&vec![length], &values)
+    }
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: PartialOrd,
+    DType: Float,
+    DType: FromPrimitive,
+{
+    // Counts the values in bins equal-width bins between min and max.
+    // The last bin includes max, values outside the range are ignored.
+    // Without a range, the minimum and maximum of the tensor are used
+    pub fn _histogram(&self, bins: usize, range: Option<(DType, DType)>) -> Tensor<u32> {
+        if bins == 0 {
+            panic!("Histogram needs at least one bin");
+        }
+        let (min, max) = match range {
+            Some(r) => r,
+            None => {
+                let mut min = DType::infinity();
+                let mut max = DType::neg_infinity();
+                for i in 0..self.size {
+                    let v = self.get_ix(i);
+                    if v < min {
+                        min = v;
+                    }
+                    if v > max {
+                        max = v;
+                    }
+                }
+                if self.size == 0 {
+                    (zero(), one())
+                } else if min == max {
+                    (
+                        min - DType::from_f32(0.5).unwrap(),
+                        max + DType::from_f32(0.5).unwrap(),
+                    )
+                } else {
+                    (min, max)
+                }
+            }
+        };
+
+        let num_bins = match DType::from_usize(bins) {
+            Some(b) => b,
+            None => panic!("Can not represent number of bins for given dtype"),
+        };
+        let width = (max - min) / num_bins;
+
+        let mut counts = vec![0; bins];
+        for i in 0..self.size {
+            let v = self.get_ix(i);
+            if v < min || v > max || v.is_nan() {
+                continue;
+            }
+            let mut bin = ((v - min) / width).floor().to_usize().unwrap_or(0);
+            if bin >= bins {
+                bin = bins - 1;
+            }
+            counts[bin] += 1;
+        }
+
+        Tensor::new_from_shape(&vec![bins], &counts)
+    }
+
+    pub fn histogram_range(&self, bins: u32, min: DType, max: DType) -> Tensor<u32> {
+        self._histogram(bins as usize, Some((min, max)))
+    }
+
+    pub fn histogram(&self, bins: u32) -> Tensor<u32> {
+        self._histogram(bins as usize, None)
+    }
+}
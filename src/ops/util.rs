@@ -6,6 +6,7 @@ use js_sys::Uint32Array;
 
 use crate::shape::*;
 use crate::tensor::*;
+use crate::utils::DepthToSpaceMode;
 
 impl<DType> Tensor<DType>
 where
@@ -194,6 +195,76 @@ where
 
         Tensor::new(result_shape, result_strides, result_size, values)
     }
+
+    // ONNX DepthToSpace on an input of shape [N, C * blocksize^2, H, W],
+    // the output has shape [N, C, H * blocksize, W * blocksize]
+    pub fn _depth_to_space(&self, blocksize: usize, mode: DepthToSpaceMode) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let CB = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        if CB % (blocksize * blocksize) != 0 {
+            panic!("Channels of depth to space have to be divisible by blocksize^2");
+        }
+        let C = CB / (blocksize * blocksize);
+
+        let result_shape = vec![N, C, H * blocksize, W * blocksize];
+        let result_strides = compute_strides(&result_shape);
+        let result_size = get_size(&result_shape);
+        let mut values = Vec::with_capacity(result_size);
+
+        for n in 0..N {
+            for c in 0..C {
+                for oh in 0..H * blocksize {
+                    let (h, block_h) = (oh / blocksize, oh % blocksize);
+                    for ow in 0..W * blocksize {
+                        let (w, block_w) = (ow / blocksize, ow % blocksize);
+                        let ic = mode.blocked_channel(c, block_h, block_w, C, blocksize);
+                        values.push(self.get_ix(((n * CB + ic) * H + h) * W + w));
+                    }
+                }
+            }
+        }
+
+        Tensor::new(result_shape, result_strides, result_size, values)
+    }
+
+    // Inverse of depth to space on an input of shape [N, C, H, W], the output has
+    // shape [N, C * blocksize^2, H / blocksize, W / blocksize]. ONNX SpaceToDepth uses DCR
+    pub fn _space_to_depth(&self, blocksize: usize, mode: DepthToSpaceMode) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        if H % blocksize != 0 || W % blocksize != 0 {
+            panic!("Spatial size of space to depth has to be divisible by blocksize");
+        }
+        let (OH, OW) = (H / blocksize, W / blocksize);
+        let CB = C * blocksize * blocksize;
+
+        let result_shape = vec![N, CB, OH, OW];
+        let result_strides = compute_strides(&result_shape);
+        let result_size = get_size(&result_shape);
+        let mut values = vec![zero(); result_size];
+
+        // Iterates over the input in order, scattering into the output
+        let mut i = 0;
+        for n in 0..N {
+            for c in 0..C {
+                for h in 0..H {
+                    let (oh, block_h) = (h / blocksize, h % blocksize);
+                    for w in 0..W {
+                        let (ow, block_w) = (w / blocksize, w % blocksize);
+                        let oc = mode.blocked_channel(c, block_h, block_w, C, blocksize);
+                        values[((n * CB + oc) * OH + oh) * OW + ow] = self.get_ix(i);
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        Tensor::new(result_shape, result_strides, result_size, values)
+    }
 }
 
 impl<DType> Tensor<DType>
@@ -201,6 +272,23 @@ where
     DType: Copy,
     DType: Num,
 {
+    // Mode: 0 == DCR, 1 == CRD
+    pub fn depth_to_space(&self, blocksize: u32, mode: u32) -> Tensor<DType> {
+        self._depth_to_space(blocksize as usize, DepthToSpaceMode::from_u32(mode))
+    }
+
+    pub fn space_to_depth(&self, blocksize: u32) -> Tensor<DType> {
+        self._space_to_depth(blocksize as usize, DepthToSpaceMode::Dcr)
+    }
+
+    pub fn pixel_shuffle(&self, upscale_factor: u32) -> Tensor<DType> {
+        self._depth_to_space(upscale_factor as usize, DepthToSpaceMode::Crd)
+    }
+
+    pub fn pixel_unshuffle(&self, downscale_factor: u32) -> Tensor<DType> {
+        self._space_to_depth(downscale_factor as usize, DepthToSpaceMode::Crd)
+    }
+
     pub fn set_values(&self, values: &Tensor<DType>, starts: Uint32Array) -> Tensor<DType> {
         let mut _starts: Vec<usize> = vec![0; starts.length() as usize];
         for i in 0..starts.length() {
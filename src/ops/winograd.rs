@@ -0,0 +1,292 @@
+use crate::ops::activation::*;
+use crate::ops::conv::conv_parameters;
+use crate::shape::*;
+use crate::tensor::*;
+use js_sys::Uint32Array;
+use num_traits::zero;
+use num_traits::Float;
+use num_traits::FromPrimitive;
+use num_traits::Num;
+
+// Winograd F(2x2, 3x3): every 4x4 input tile produces a 2x2 output tile
+const TILE_SIZE: usize = 4;
+const OUTPUT_TILE_SIZE: usize = 2;
+
+// Applies the input transform B^T d B to a 4x4 tile
+#[inline]
+fn transform_input<DType>(d: &[DType; 16]) -> [DType; 16]
+where
+    DType: Copy,
+    DType: Num,
+{
+    let mut t = [zero(); 16];
+    for j in 0..4 {
+        t[j] = d[j] - d[8 + j];
+        t[4 + j] = d[4 + j] + d[8 + j];
+        t[8 + j] = d[8 + j] - d[4 + j];
+        t[12 + j] = d[4 + j] - d[12 + j];
+    }
+
+    let mut v = [zero(); 16];
+    for i in 0..4 {
+        v[i * 4] = t[i * 4] - t[i * 4 + 2];
+        v[i * 4 + 1] = t[i * 4 + 1] + t[i * 4 + 2];
+        v[i * 4 + 2] = t[i * 4 + 2] - t[i * 4 + 1];
+        v[i * 4 + 3] = t[i * 4 + 1] - t[i * 4 + 3];
+    }
+    v
+}
+
+// Applies the output transform A^T m A, returning the 2x2 output tile
+#[inline]
+fn transform_output<DType>(m: &[DType; 16]) -> [DType; 4]
+where
+    DType: Copy,
+    DType: Num,
+{
+    let mut t = [zero(); 8];
+    for j in 0..4 {
+        t[j] = m[j] + m[4 + j] + m[8 + j];
+        t[4 + j] = m[4 + j] - m[8 + j] - m[12 + j];
+    }
+
+    [
+        t[0] + t[1] + t[2],
+        t[1] - t[2] - t[3],
+        t[4] + t[5] + t[6],
+        t[5] - t[6] - t[7],
+    ]
+}
+
+// Whether _conv_winograd can compute the convolution
+pub fn winograd_applicable(
+    kernel_shape: &Vec<usize>,
+    dilations: &Vec<usize>,
+    strides: &Vec<usize>,
+) -> bool {
+    kernel_shape.len() == 4
+        && kernel_shape[2] == 3
+        && kernel_shape[3] == 3
+        && dilations.iter().all(|&d| d == 1)
+        && strides.iter().all(|&s| s == 1)
+}
+
+impl<DType> Tensor<DType>
+where
+    DType: Copy,
+    DType: Num,
+    DType: FromPrimitive,
+    DType: PartialOrd,
+    DType: Float,
+{
+    // Transforms a [M, CG, 3, 3] kernel to [M, CG, 4, 4] by G g G^T. The result
+    // only depends on the kernel and can be reused for every convolution with it
+    pub fn _winograd_kernel(&self) -> Tensor<DType> {
+        let M = self.get_dim_size(0);
+        let CG = self.get_dim_size(1);
+        if self.get_dim_size(2) != 3 || self.get_dim_size(3) != 3 {
+            panic!("Winograd convolution requires a 3x3 kernel");
+        }
+
+        let half = DType::from_f32(0.5).unwrap();
+
+        let mut values = vec![zero(); M * CG * TILE_SIZE * TILE_SIZE];
+        for k in 0..M * CG {
+            let mut g = [zero(); 9];
+            for i in 0..9 {
+                g[i] = self.get_ix(k * 9 + i);
+            }
+
+            // t = G g, a 4x3 matrix
+            let mut t = [zero(); 12];
+            for j in 0..3 {
+                t[j] = g[j];
+                t[3 + j] = (g[j] + g[3 + j] + g[6 + j]) * half;
+                t[6 + j] = (g[j] - g[3 + j] + g[6 + j]) * half;
+                t[9 + j] = g[6 + j];
+            }
+
+            // u = t G^T
+            let base = k * TILE_SIZE * TILE_SIZE;
+            for i in 0..4 {
+                values[base + i * 4] = t[i * 3];
+                values[base + i * 4 + 1] = (t[i * 3] + t[i * 3 + 1] + t[i * 3 + 2]) * half;
+                values[base + i * 4 + 2] = (t[i * 3] - t[i * 3 + 1] + t[i * 3 + 2]) * half;
+                values[base + i * 4 + 3] = t[i * 3 + 2];
+            }
+        }
+
+        Tensor::new_from_shape(&vec![M, CG, TILE_SIZE, TILE_SIZE], &values)
+    }
+
+    // 3x3 stride 1 convolution of a [N, C, H, W] tensor with a kernel
+    // transformed by _winograd_kernel. Output channel m reads the input
+    // channels (m * CG + cg) % C as in _conv
+    pub fn _conv_winograd(
+        &self,
+        transformed_kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        group: usize,
+        pads: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let N = self.get_dim_size(0);
+        let C = self.get_dim_size(1);
+        let H = self.get_dim_size(2);
+        let W = self.get_dim_size(3);
+        let M = transformed_kernel.get_dim_size(0);
+        let CG = transformed_kernel.get_dim_size(1);
+        if CG * group != C {
+            panic!(
+                "Kernel with {} channels does not match {} input channels in {} groups",
+                CG, C, group
+            );
+        }
+
+        let output_height = H + pads[0] + pads[2] - 2;
+        let output_width = W + pads[1] + pads[3] - 2;
+        let tiles_y = (output_height + OUTPUT_TILE_SIZE - 1) / OUTPUT_TILE_SIZE;
+        let tiles_x = (output_width + OUTPUT_TILE_SIZE - 1) / OUTPUT_TILE_SIZE;
+        let tiles = tiles_y * tiles_x;
+
+        let output_shape = vec![N, M, output_height, output_width];
+        let output_strides = compute_strides(&output_shape);
+        let output_size = output_height * output_width;
+        let o_size = get_size(&output_shape);
+        let mut values = vec![zero(); o_size];
+
+        let mut transformed_input = vec![zero(); C * tiles * 16];
+
+        // Iterate over all batches
+        for n in 0..N {
+            for c in 0..C {
+                let input_base = (n * C + c) * H * W;
+                for tile in 0..tiles {
+                    let y0 = (tile / tiles_x * OUTPUT_TILE_SIZE) as i32 - pads[0] as i32;
+                    let x0 = (tile % tiles_x * OUTPUT_TILE_SIZE) as i32 - pads[1] as i32;
+
+                    let mut d = [zero(); 16];
+                    for i in 0..TILE_SIZE {
+                        let y = y0 + i as i32;
+                        if y < 0 || y >= H as i32 {
+                            continue;
+                        }
+                        for j in 0..TILE_SIZE {
+                            let x = x0 + j as i32;
+                            if x >= 0 && x < W as i32 {
+                                d[i * 4 + j] =
+                                    self.get_ix(input_base + y as usize * W + x as usize);
+                            }
+                        }
+                    }
+
+                    let v = transform_input(&d);
+                    let base = (c * tiles + tile) * 16;
+                    transformed_input[base..base + 16].copy_from_slice(&v);
+                }
+            }
+
+            // Iterate over all output channels
+            for m in 0..M {
+                let basis = output_strides[0] * n + output_strides[1] * m;
+                let b = match bias {
+                    Some(bi) => bi.get_ix(m),
+                    None => zero(),
+                };
+
+                for tile in 0..tiles {
+                    let mut product: [DType; 16] = [zero(); 16];
+                    for cg in 0..CG {
+                        let c = (m * CG + cg) % C;
+                        let u_base = (m * CG + cg) * 16;
+                        let v_base = (c * tiles + tile) * 16;
+                        for k in 0..16 {
+                            product[k] = product[k]
+                                + transformed_kernel.get_ix(u_base + k)
+                                    * transformed_input[v_base + k];
+                        }
+                    }
+
+                    let y = transform_output(&product);
+
+                    let oy = tile / tiles_x * OUTPUT_TILE_SIZE;
+                    let ox = tile % tiles_x * OUTPUT_TILE_SIZE;
+                    for i in 0..OUTPUT_TILE_SIZE {
+                        for j in 0..OUTPUT_TILE_SIZE {
+                            if oy + i < output_height && ox + j < output_width {
+                                values[basis + (oy + i) * output_width + ox + j] = y[i * 2 + j] + b;
+                            }
+                        }
+                    }
+                }
+
+                activation.apply(&mut values[basis..basis + output_size]);
+            }
+        }
+
+        Tensor::new(output_shape, output_strides, o_size, values)
+    }
+
+    // Uses the Winograd path for 3x3 stride 1 convolutions and _conv otherwise.
+    // The kernel is transformed on every call, callers convolving with the same
+    // kernel repeatedly should cache _winograd_kernel and call _conv_winograd
+    pub fn _conv_auto(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: &Vec<usize>,
+        group: usize,
+        pads: &Vec<usize>,
+        strides: &Vec<usize>,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        if winograd_applicable(kernel.get_sh(), dilations, strides) {
+            self._conv_winograd(&kernel._winograd_kernel(), bias, group, pads, activation)
+        } else {
+            self._conv(kernel, bias, dilations, group, pads, strides, activation)
+        }
+    }
+
+    pub fn conv_auto(
+        &self,
+        kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        dilations: Uint32Array,
+        group: u32,
+        pads: Uint32Array,
+        strides: Uint32Array,
+        activation: Activation,
+    ) -> Tensor<DType> {
+        let (_dilations, _pads, _strides) = conv_parameters(dilations, pads, strides);
+        return self._conv_auto(
+            kernel,
+            bias,
+            &_dilations,
+            group as usize,
+            &_pads,
+            &_strides,
+            activation,
+        );
+    }
+
+    pub fn conv_winograd(
+        &self,
+        transformed_kernel: &Tensor<DType>,
+        bias: Option<&Tensor<DType>>,
+        group: u32,
+        pads: Uint32Array,
+        activation: u32,
+    ) -> Tensor<DType> {
+        let mut _pads: Vec<usize> = vec![0; pads.length() as usize];
+        for i in 0..pads.length() {
+            _pads[i as usize] = pads.get_index(i) as usize;
+        }
+        return self._conv_winograd(
+            transformed_kernel,
+            bias,
+            group as usize,
+            &_pads,
+            Activation::from_u32(activation),
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ops::activation::Activation;
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn activated(activation: Activation) -> Tensor<f32> {
+    let mut values = vec![-2., -0.5, 0., 1., 7.];
+    activation.apply(&mut values);
+    Tensor::new_from_shape(&vec![5], &values)
+}
+
+#[test]
+fn test_activation_relu() {
+    let expected = Tensor::new_from_shape(&vec![5], &vec![0., 0., 0., 1., 7.]);
+    assert!(activated(Activation::Relu).compare(&expected, DELTA));
+
+    let expected = Tensor::new_from_shape(&vec![5], &vec![0., 0., 0., 1., 6.]);
+    assert!(activated(Activation::Relu6).compare(&expected, DELTA));
+
+    let expected = Tensor::new_from_shape(&vec![5], &vec![-0.2, -0.05, 0., 1., 7.]);
+    assert!(activated(Activation::LeakyRelu(0.1)).compare(&expected, DELTA));
+}
+
+#[test]
+fn test_activation_clip() {
+    let expected = Tensor::new_from_shape(&vec![5], &vec![-1., -0.5, 0., 1., 2.]);
+    assert!(activated(Activation::Clip(-1., 2.)).compare(&expected, DELTA));
+}
+
+#[test]
+fn test_activation_sigmoid() {
+    let expected = Tensor::new_from_shape(
+        &vec![5],
+        &vec![0.1192029, 0.3775407, 0.5, 0.7310586, 0.9990889],
+    );
+    assert!(activated(Activation::Sigmoid).compare(&expected, DELTA));
+
+    let expected = Tensor::new_from_shape(&vec![5], &vec![0.1, 0.4, 0.5, 0.7, 1.]);
+    assert!(activated(Activation::HardSigmoid(0.2, 0.5)).compare(&expected, DELTA));
+}
+
+#[test]
+fn test_activation_hard_swish_tanh() {
+    let expected = Tensor::new_from_shape(&vec![5], &vec![-1. / 3., -0.2083333, 0., 0.6666667, 7.]);
+    assert!(activated(Activation::HardSwish).compare(&expected, DELTA));
+
+    let expected = Tensor::new_from_shape(
+        &vec![5],
+        &vec![-0.9640276, -0.4621172, 0., 0.7615942, 0.9999983],
+    );
+    assert!(activated(Activation::Tanh).compare(&expected, DELTA));
+}
+
+#[test]
+fn test_activation_integer() {
+    let mut values: Vec<i32> = vec![-3, 2, 9];
+    Activation::Relu6.apply(&mut values);
+    assert_eq!(values, vec![0, 2, 6]);
+
+    let mut values: Vec<i32> = vec![-3, 2, 9];
+    Activation::Clip(-1., 4.).apply(&mut values);
+    assert_eq!(values, vec![-1, 2, 4]);
+}
+
+#[test]
+fn test_activation_from_parameters() {
+    assert_eq!(
+        Activation::from_parameters(1, 0., 0.),
+        Activation::from_u32(1)
+    );
+    assert_eq!(
+        Activation::from_parameters(3, 0.2, 0.),
+        Activation::LeakyRelu(0.2)
+    );
+    assert_eq!(
+        Activation::from_parameters(4, -1., 1.),
+        Activation::Clip(-1., 1.)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_activation_from_u32_requires_parameters() {
+    Activation::from_u32(3);
+}
+
+#[test]
+fn test_gemm_fused() {
+    let a = Tensor::new_from_shape(&vec![2, 2], &vec![1., -2., 3., 4.]);
+    let b = Tensor::new_from_shape(&vec![2, 2], &vec![5., 6., 7., 8.]);
+    let c = Tensor::new_from_shape(&vec![2, 2], &vec![1., 1., -60., 1.]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![-0.9, -0.9, -0.9, 6.]);
+
+    let result = a.gemm_fused(
+        &b,
+        false,
+        false,
+        1.0,
+        Some(&c),
+        1.0,
+        Activation::Clip(-1., 6.),
+    );
+    let result = result.gemm_fused(
+        &Tensor::new_from_shape(&vec![2, 2], &vec![1., 0., 0., 1.]),
+        false,
+        false,
+        1.0,
+        None,
+        0.0,
+        Activation::LeakyRelu(0.9),
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
@@ -1,6 +1,9 @@
 use crate::assert_delta;
+use crate::ops::accumulate::*;
 use crate::ops::aggregate::*;
 use crate::tensor::*;
+#[cfg(test)]
+use crate::tests::tensor::gradient::pseudo_random_tensor;
 use crate::utils::uint32_array;
 
 const DELTA: f32 = 0.00001;
@@ -241,3 +244,246 @@ fn test_tensor_max_special_case() {
 
     assert!(res1.compare(&expected1, DELTA));
 }
+
+#[cfg(test)]
+fn relative_error(value: f32, reference: f64) -> f64 {
+    ((value as f64 - reference) / reference).abs()
+}
+
+#[test]
+fn test_tensor_sum_with_mode_accuracy() {
+    let size = 1 << 20;
+    let values = pseudo_random_tensor(&vec![size], 12345)
+        .abs()
+        .get_values()
+        .clone();
+    let reference: f64 = values.iter().map(|&x| x as f64).sum();
+
+    let a = Tensor::new_from_shape(&vec![size], &values);
+
+    let sequential = a._sum_with_mode(&vec![0], false, SumMode::Sequential);
+    let pairwise = a._sum_with_mode(&vec![0], false, SumMode::Pairwise);
+    let kahan = a._sum_with_mode(&vec![0], false, SumMode::Kahan);
+
+    let sequential_error = relative_error(sequential.get_ix(0), reference);
+    let pairwise_error = relative_error(pairwise.get_ix(0), reference);
+    let kahan_error = relative_error(kahan.get_ix(0), reference);
+
+    // The sequential error depends on the data, pairwise and Kahan sums are far more accurate
+    assert!(sequential_error > 10. * pairwise_error);
+    assert!(pairwise_error < 1e-6);
+    assert!(kahan_error < 1e-7);
+}
+
+#[test]
+fn test_tensor_reduce_mean_with_mode_accuracy() {
+    let values = pseudo_random_tensor(&vec![2 * 1024 * 1024], 12345)
+        .abs()
+        .get_values()
+        .clone();
+    let a = Tensor::new_from_shape(&vec![1, 2, 1024, 1024], &values);
+
+    let kahan = a._reduce_mean_with_mode(&vec![2, 3], true, SumMode::Kahan);
+    let pairwise = a._reduce_mean_with_mode(&vec![2, 3], true, SumMode::Pairwise);
+
+    assert_eq!(kahan.get_sh(), &vec![1, 2, 1, 1]);
+    for c in 0..2 {
+        let channel = &values[c * 1024 * 1024..(c + 1) * 1024 * 1024];
+        let reference = channel.iter().map(|&x| x as f64).sum::<f64>() / channel.len() as f64;
+
+        assert!(relative_error(kahan.get_ix(c), reference) < 1e-6);
+        assert!(relative_error(pairwise.get_ix(c), reference) < 1e-6);
+    }
+}
+
+#[test]
+fn test_tensor_sum_square_with_mode_accuracy() {
+    let size = 1 << 20;
+    let values = pseudo_random_tensor(&vec![size], 12345)
+        .abs()
+        .get_values()
+        .clone();
+    let reference: f64 = values.iter().map(|&x| (x as f64) * (x as f64)).sum();
+
+    let a = Tensor::new_from_shape(&vec![size], &values);
+
+    let kahan = a._sum_square_with_mode(&vec![0], false, SumMode::Kahan);
+    let pairwise = a._sum_square_with_mode(&vec![0], false, SumMode::Pairwise);
+    let mean_square = a._reduce_mean_square_with_mode(&vec![0], false, SumMode::Kahan);
+
+    assert!(relative_error(kahan.get_ix(0), reference) < 1e-6);
+    assert!(relative_error(pairwise.get_ix(0), reference) < 1e-6);
+    assert!(relative_error(mean_square.get_ix(0), reference / size as f64) < 1e-6);
+}
+
+#[test]
+fn test_tensor_sum_with_mode_non_continuous_axes() {
+    let a = Tensor::new_from_shape(
+        &vec![2, 3, 4],
+        &vec![
+            1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 11., 12., 13., 14., 15., 16., 17., 18., 19.,
+            20., 21., 22., 23., 24.,
+        ],
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 3, 1], &vec![68., 100., 132.]);
+
+    for mode in vec![SumMode::Sequential, SumMode::Pairwise, SumMode::Kahan] {
+        let result = a._sum_with_mode(&vec![0, 2], true, mode);
+
+        assert!(result.compare(&expected, DELTA));
+    }
+}
+
+#[test]
+fn test_tensor_reduce_mean_widened_u8() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![2, 3], &vec![200, 250, 255, 1, 2, 2]);
+    let expected = Tensor::new_from_shape(&vec![2, 1], &vec![235, 2]);
+
+    let result = a._reduce_mean_widened(&vec![1], true);
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_tensor_sum_widened_saturates() {
+    let a: Tensor<i8> = Tensor::new_from_shape(&vec![2, 2], &vec![100, 100, -100, -100]);
+    let expected = Tensor::new_from_shape(&vec![2], &vec![127, -128]);
+    let expected_wide = Tensor::new_from_shape(&vec![2], &vec![200, -200]);
+
+    assert!(a._sum_widened(&vec![1], false) == expected);
+    assert!(a._sum_wide(&vec![1], false) == expected_wide);
+}
+
+#[test]
+fn test_tensor_wide_u16_max() {
+    let a: Tensor<u16> = Tensor::new_from_shape(&vec![2], &vec![u16::MAX, u16::MAX]);
+    let max = u16::MAX as i64;
+
+    let square = a._sum_square_wide(&vec![0], false);
+    assert!(square == Tensor::new_from_shape(&vec![1], &vec![2 * max * max]));
+    // The mean square saturates instead of wrapping
+    let mean_square = a._reduce_mean_square_widened(&vec![0], false);
+    assert!(mean_square == Tensor::new_from_shape(&vec![1], &vec![u16::MAX]));
+
+    let long: Tensor<u16> = Tensor::new_from_shape(&vec![40000], &vec![u16::MAX; 40000]);
+    let sum = long._sum_wide(&vec![0], false);
+    assert!(sum == Tensor::new_from_shape(&vec![1], &vec![40000 * max]));
+
+    let b: Tensor<i16> = Tensor::new_from_shape(&vec![1, 2], &vec![i16::MIN, i16::MIN]);
+    let c: Tensor<i16> = Tensor::new_from_shape(&vec![2, 1], &vec![i16::MIN, i16::MIN]);
+    let min = i16::MIN as i64;
+    let product = b._gemm_wide(&c, false, false, 1, None, 0);
+    assert!(product == Tensor::new_from_shape(&vec![1, 1], &vec![2 * min * min]));
+}
+
+#[test]
+fn test_tensor_wide_u8_image() {
+    // The sum of squares of a 256x256 image of 255 exceeds i32
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![1, 256, 256], &vec![u8::MAX; 256 * 256]);
+    let max = u8::MAX as i64;
+
+    let square = a._sum_square_wide(&vec![1, 2], false);
+    assert!(square == Tensor::new_from_shape(&vec![1], &vec![256 * 256 * max * max]));
+    let mean_square = a._reduce_mean_square_widened(&vec![1, 2], false);
+    assert!(mean_square == Tensor::new_from_shape(&vec![1], &vec![u8::MAX]));
+
+    let b: Tensor<i8> = Tensor::new_from_shape(&vec![256 * 256], &vec![i8::MIN; 256 * 256]);
+    let square = b._sum_square_wide(&vec![0], false);
+    let min = i8::MIN as i64;
+    assert!(square == Tensor::new_from_shape(&vec![1], &vec![256 * 256 * min * min]));
+}
+
+#[test]
+fn test_tensor_reduce_mean_square_widened_rounds() {
+    let a: Tensor<i16> = Tensor::new_from_shape(&vec![4], &vec![1, -2, 2, 0]);
+    let expected = Tensor::new_from_shape(&vec![1], &vec![2]);
+
+    assert!(a._reduce_mean_square_widened(&vec![0], false) == expected);
+}
+
+#[test]
+fn test_tensor_sum_backward() {
+    let a = Tensor::new_from_shape(&vec![2, 3], &vec![1., 2., 3., 4., 5., 6.]);
+    let output = a._sum(&vec![1], false);
+    let grad = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let expected = Tensor::new_from_shape(&vec![2, 3], &vec![1., 1., 1., 2., 2., 2.]);
+
+    let result = a._sum_backward(&output, &grad, &vec![1], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_reduce_mean_backward_keep_dims() {
+    let a = Tensor::new_from_shape(&vec![2, 3, 2], &vec![0.; 12]);
+    let output = a._reduce_mean(&vec![0, 2], true);
+    let grad = Tensor::new_from_shape(&vec![1, 3, 1], &vec![4., 8., 12.]);
+    let expected = Tensor::new_from_shape(
+        &vec![2, 3, 2],
+        &vec![1., 1., 2., 2., 3., 3., 1., 1., 2., 2., 3., 3.],
+    );
+
+    let result = a._reduce_mean_backward(&output, &grad, &vec![0, 2], true);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_sum_square_backward() {
+    let a = Tensor::new_from_shape(&vec![2, 2], &vec![1., -2., 3., 4.]);
+    let output = a._sum_square(&vec![0, 1], false);
+    let grad = Tensor::new_from_shape(&vec![1], &vec![0.5]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![1., -2., 3., 4.]);
+
+    let result = a._sum_square_backward(&output, &grad, &vec![0, 1], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_max_backward_ties() {
+    let a = Tensor::new_from_shape(&vec![2, 3], &vec![3., 1., 3., 4., 5., 6.]);
+    let output = a._max(&vec![1], false);
+    let grad = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let expected = Tensor::new_from_shape(&vec![2, 3], &vec![0.5, 0., 0.5, 0., 0., 2.]);
+
+    let result = a._max_backward(&output, &grad, &vec![1], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_min_backward() {
+    let a = Tensor::new_from_shape(&vec![2, 2], &vec![3., 1., 0., 5.]);
+    let output = a._min(&vec![0], false);
+    let grad = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![0., 2., 1., 0.]);
+
+    let result = a._min_backward(&output, &grad, &vec![0], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_product_backward_zeros() {
+    let a = Tensor::new_from_shape(&vec![3, 3], &vec![2., 3., 4., 2., 0., 4., 0., 3., 0.]);
+    let output = a._product(&vec![1], false);
+    let grad = Tensor::new_from_shape(&vec![3], &vec![1., 1., 1.]);
+    let expected = Tensor::new_from_shape(&vec![3, 3], &vec![12., 8., 6., 0., 8., 0., 0., 0., 0.]);
+
+    let result = a._product_backward(&output, &grad, &vec![1], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_tensor_reduce_log_sum_exp_backward() {
+    let a = Tensor::new_from_shape(&vec![1, 3], &vec![1., 2., 3.]);
+    let output = a._reduce_log_sum_exp(&vec![1], false);
+    let grad = Tensor::new_from_shape(&vec![1], &vec![1.]);
+    let expected = Tensor::new_from_shape(&vec![1, 3], &vec![0.09003057, 0.24472847, 0.66524096]);
+
+    let result = a._reduce_log_sum_exp_backward(&output, &grad, &vec![1], false);
+
+    assert!(result.compare(&expected, DELTA));
+}
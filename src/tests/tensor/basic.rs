@@ -223,3 +223,15 @@ fn test_tensor_repeat() {
     assert!(result3.compare(&expected3, DELTA));
     assert!(result4.compare(&expected4, DELTA));
 }
+
+#[test]
+fn test_tensor_gemm_widened_u8() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![2, 2], &vec![100, 2, 3, 4]);
+    let b: Tensor<u8> = Tensor::new_from_shape(&vec![2, 2], &vec![5, 6, 7, 8]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![255, 255, 43, 50]);
+    let expected_wide = Tensor::new_from_shape(&vec![2, 2], &vec![514, 616, 43, 50]);
+
+    assert!(a._gemm_wide(&b, false, false, 1, None, 0) == expected_wide);
+    assert!(a.gemm_widened(&b, false, false, 1) == expected);
+    assert!(a.matmul_widened(&b) == expected);
+}
@@ -0,0 +1,160 @@
+use crate::ops::activation::Activation;
+use crate::tensor::*;
+#[cfg(test)]
+use crate::tests::tensor::gradient::pseudo_random_tensor;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn to_channels_last(x: &Tensor<f32>) -> Tensor<f32> {
+    x._transpose(&vec![0, 2, 3, 1])
+}
+
+#[test]
+fn test_conv_channels_last_matches_channels_first() {
+    // [input shape, kernel shape, dilations, pads, strides]
+    let cases: Vec<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>)> = vec![
+        (
+            vec![1, 2, 5, 5],
+            vec![3, 2, 3, 3],
+            vec![1, 1],
+            vec![1, 1, 1, 1],
+            vec![1, 1],
+        ),
+        (
+            vec![2, 4, 6, 5],
+            vec![4, 2, 2, 3],
+            vec![2, 1],
+            vec![0, 1, 1, 0],
+            vec![2, 1],
+        ),
+        (
+            vec![1, 3, 4, 4],
+            vec![3, 1, 3, 3],
+            vec![1, 1],
+            vec![1, 1, 1, 1],
+            vec![2, 2],
+        ),
+    ];
+
+    for (i, (shape, kernel_shape, dilations, pads, strides)) in cases.iter().enumerate() {
+        let x = pseudo_random_tensor(shape, 3 + i as u32);
+        let w = pseudo_random_tensor(kernel_shape, 29 + i as u32);
+        let b = pseudo_random_tensor(&vec![kernel_shape[0]], 41 + i as u32);
+        let group = shape[1] / kernel_shape[1];
+
+        for bias in vec![None, Some(&b)] {
+            let expected =
+                x._conv_direct(&w, bias, dilations, group, pads, strides, Activation::Relu);
+            let result = to_channels_last(&x)._conv_channels_last(
+                &w,
+                bias,
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::Relu,
+            );
+
+            assert!(result == to_channels_last(&expected));
+        }
+    }
+}
+
+#[test]
+fn test_conv_channels_last_widened() {
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 2, 2, 1], &vec![200, 100, 50, 0]);
+    let w: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 1], &vec![1, 1]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 2, 1], &vec![250, 100]);
+
+    let result = x._conv_channels_last_widened(
+        &w,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![0; 4],
+        &vec![1, 1],
+        Activation::None,
+    );
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_conv_transpose_channels_last_matches_channels_first() {
+    let x = pseudo_random_tensor(&vec![1, 2, 3, 4], 7);
+    let w = pseudo_random_tensor(&vec![3, 2, 3, 2], 19);
+    let dilations = vec![1, 2];
+    let pads = vec![2, 1, 1, 0];
+    let strides = vec![2, 1];
+
+    let expected = x._conv_transpose(&w, &dilations, 1, &pads, &strides);
+    let result =
+        to_channels_last(&x)._conv_transpose_channels_last(&w, &dilations, 1, &pads, &strides);
+
+    assert!(result == to_channels_last(&expected));
+}
+
+#[test]
+fn test_average_pool_channels_last() {
+    let x = pseudo_random_tensor(&vec![2, 3, 5, 4], 11);
+    let kernel_shape = vec![3, 2];
+    let pads = vec![1, 0, 1, 1];
+    let strides = vec![2, 1];
+
+    for include_pad in vec![false, true] {
+        let expected = x._average_pool(&kernel_shape, &pads, &strides, include_pad);
+        let result = to_channels_last(&x)._average_pool_channels_last(
+            &kernel_shape,
+            &pads,
+            &strides,
+            include_pad,
+        );
+
+        assert!(result == to_channels_last(&expected));
+    }
+}
+
+#[test]
+fn test_average_pool_channels_last_widened() {
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![255, 250, 254, 251]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 2], &vec![255, 251]);
+
+    let result =
+        x._average_pool_channels_last_widened(&vec![1, 2], &vec![0; 4], &vec![1, 1], false);
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_max_pool_channels_last() {
+    let x = pseudo_random_tensor(&vec![1, 2, 4, 5], 23);
+    let kernel_shape = vec![2, 2];
+    let pads = vec![1, 1, 0, 0];
+    let strides = vec![2, 2];
+    let dilations = vec![1, 2];
+
+    let (expected, _) = x._max_pool(&kernel_shape, &pads, &strides, &dilations, true, 0);
+    let x_last = to_channels_last(&x);
+    let (result, indices) =
+        x_last._max_pool_channels_last(&kernel_shape, &pads, &strides, &dilations, true);
+
+    assert!(result == to_channels_last(&expected));
+    for i in 0..indices.get_values().len() {
+        assert!(x_last.get_ix(indices.get_ix(i) as usize) == result.get_ix(i));
+    }
+}
+
+#[test]
+fn test_normalize_channels_last() {
+    let x = Tensor::new_from_shape(&vec![1, 2, 2], &vec![1., 4., 3., 8.]);
+    let mean = Tensor::new_from_shape(&vec![2], &vec![2., 6.]);
+    let variance = Tensor::new_from_shape(&vec![2], &vec![1., 4.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let expected = Tensor::new_from_shape(&vec![1, 2, 2], &vec![-1., -1., 1., 3.]);
+
+    let result = x.normalize_channels_last(&mean, &variance, 0., &scale, &bias);
+
+    assert!(result.compare(&expected, DELTA));
+}
@@ -1,6 +1,18 @@
 use crate::assert_delta;
+use crate::ops::activation::Activation;
 use crate::tensor::*;
+#[cfg(test)]
+use crate::tests::tensor::gradient::numerical_gradient;
+#[cfg(test)]
+use crate::tests::tensor::gradient::pseudo_random_tensor;
+use crate::utils::auto_pads;
+use crate::utils::conv_auto_pads;
+use crate::utils::pool_auto_pads;
 use crate::utils::uint32_array;
+#[cfg(test)]
+use crate::utils::AutoPad;
+#[cfg(test)]
+use crate::utils::PadMode;
 
 const DELTA: f32 = 0.00001;
 
@@ -17,8 +29,16 @@ fn test_tensor_conv() {
     let expected1 = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![10.]);
     let expected2 = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![15.]);
 
-    let result1 = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
-    let result2 = x._conv(&w, Some(&b), &dilations, 1, &pads, &strides, 0);
+    let result1 = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
+    let result2 = x._conv(
+        &w,
+        Some(&b),
+        &dilations,
+        1,
+        &pads,
+        &strides,
+        Activation::None,
+    );
 
     assert!(result1.compare(&expected1, DELTA));
     assert!(result2.compare(&expected2, DELTA));
@@ -45,7 +65,7 @@ fn test_tensor_grouped() {
         ],
     );
 
-    let result = x._conv(&w, None, &dilations, 2, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 2, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -61,7 +81,7 @@ fn test_conv_1d() {
 
     let expected = Tensor::new_from_shape(&vec![1, 1, 4], &vec![14., 20., 26., 32.]);
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 1);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::Relu);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -77,7 +97,7 @@ fn test_conv_1d_relu() {
 
     let expected = Tensor::new_from_shape(&vec![1, 1, 4], &vec![14., 0.0, 10.0, 24.0]);
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 1);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::Relu);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -99,7 +119,7 @@ fn test_conv_3d() {
         &vec![356., 392., 464., 500., 680., 716., 788., 824.],
     );
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -118,7 +138,7 @@ fn test_conv_dilated() {
 
     let expected = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![78., 88., 118., 128.]);
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -137,7 +157,7 @@ fn test_conv_strided() {
 
     let expected = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![44., 64., 124., 144.]);
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -156,7 +176,7 @@ fn test_conv_padded() {
         &vec![4., 11., 6., 14., 30., 14., 6., 11., 4.],
     );
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -185,7 +205,7 @@ fn test_conv_multiple_filters() {
         ],
     );
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 
     assert!(result.compare(&expected, DELTA));
 }
@@ -216,7 +236,737 @@ fn test_conv_back() {
         ],
     );
 
-    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
-    let result2 = x._conv(&w, None, &dilations, 1, &pads, &strides, 0);
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
 }
 //[1, 1, 3, 3], [1, 1, 4, 4], [1, 1], [1, 1, 1, 1], [1, 1]
+
+#[test]
+fn test_conv_widened_u8() {
+    let strides: Vec<usize> = vec![1; 2];
+    let pads: Vec<usize> = vec![0; 4];
+    let dilations: Vec<usize> = vec![1; 2];
+
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![100, 100, 100, 1]);
+    let w: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1, 1, 1, 1]);
+    let b: Tensor<u8> = Tensor::new_from_shape(&vec![1], &vec![5]);
+
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![255]);
+    let expected_wide = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![306]);
+
+    let result = x._conv_widened(
+        &w,
+        Some(&b),
+        &dilations,
+        1,
+        &pads,
+        &strides,
+        Activation::None,
+    );
+    let result_wide = x._conv_wide(
+        &w,
+        Some(&b),
+        &dilations,
+        1,
+        &pads,
+        &strides,
+        Activation::None,
+    );
+
+    assert!(result == expected);
+    assert!(result_wide == expected_wide);
+}
+
+#[cfg(test)]
+fn activations() -> Vec<Activation> {
+    vec![
+        Activation::None,
+        Activation::Relu,
+        Activation::Relu6,
+        Activation::LeakyRelu(0.1),
+        Activation::Clip(-0.5, 0.5),
+        Activation::Sigmoid,
+        Activation::HardSigmoid(0.2, 0.5),
+        Activation::HardSwish,
+        Activation::Tanh,
+    ]
+}
+
+#[test]
+fn test_conv_im2col_matches_direct() {
+    // [input shape, kernel shape, dilations, pads, strides]
+    let cases: Vec<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>)> = vec![
+        (
+            vec![2, 3, 7, 6],
+            vec![4, 3, 3, 3],
+            vec![1, 1],
+            vec![1, 1, 1, 1],
+            vec![1, 1],
+        ),
+        (
+            vec![1, 4, 9, 9],
+            vec![4, 2, 3, 2],
+            vec![2, 1],
+            vec![0, 1, 2, 0],
+            vec![2, 1],
+        ),
+        (
+            vec![1, 4, 6, 6],
+            vec![4, 1, 3, 3],
+            vec![1, 1],
+            vec![1, 1, 1, 1],
+            vec![1, 1],
+        ),
+        (
+            vec![1, 6, 5, 5],
+            vec![9, 2, 1, 1],
+            vec![1, 1],
+            vec![0, 0, 0, 0],
+            vec![2, 2],
+        ),
+        (vec![2, 2, 11], vec![3, 2, 4], vec![3], vec![2, 1], vec![1]),
+        (
+            vec![1, 2, 4, 4, 5],
+            vec![2, 2, 2, 3, 2],
+            vec![1, 1, 2],
+            vec![1, 0, 1, 0, 1, 1],
+            vec![1, 2, 1],
+        ),
+    ];
+
+    for (i, (shape, kernel_shape, dilations, pads, strides)) in cases.iter().enumerate() {
+        let x = pseudo_random_tensor(shape, 17 + i as u32);
+        let w = pseudo_random_tensor(kernel_shape, 91 + i as u32);
+        let b = pseudo_random_tensor(&vec![kernel_shape[0]], 7 + i as u32);
+        let group = shape[1] / kernel_shape[1];
+
+        for activation in activations() {
+            for bias in vec![None, Some(&b)] {
+                let direct = x._conv_direct(&w, bias, dilations, group, pads, strides, activation);
+                let im2col = x._conv_im2col(&w, bias, dilations, group, pads, strides, activation);
+
+                assert!(direct == im2col);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_conv_im2col_integer() {
+    let x: Tensor<i32> = Tensor::new_from_shape(&vec![1, 2, 3, 3], &(0..18).collect());
+    let w: Tensor<i32> = Tensor::new_from_shape(&vec![2, 2, 2, 2], &(-8..8).collect());
+    let b: Tensor<i32> = Tensor::new_from_shape(&vec![2], &vec![3, -100]);
+
+    let direct = x._conv_direct(
+        &w,
+        Some(&b),
+        &vec![1, 1],
+        1,
+        &vec![1, 0, 0, 1],
+        &vec![1, 1],
+        Activation::Relu,
+    );
+    let im2col = x._conv_im2col(
+        &w,
+        Some(&b),
+        &vec![1, 1],
+        1,
+        &vec![1, 0, 0, 1],
+        &vec![1, 1],
+        Activation::Relu,
+    );
+
+    assert!(direct == im2col);
+}
+
+#[test]
+#[should_panic(expected = "Kernel with 2 channels does not match 2 input channels in 2 groups")]
+fn test_conv_im2col_group_mismatch() {
+    let x = pseudo_random_tensor(&vec![1, 2, 3, 3], 23);
+    let w = pseudo_random_tensor(&vec![2, 2, 2, 2], 43);
+
+    x._conv_im2col(
+        &w,
+        None,
+        &vec![1, 1],
+        2,
+        &vec![0; 4],
+        &vec![1, 1],
+        Activation::None,
+    );
+}
+
+#[test]
+fn test_conv_selects_im2col_for_large_inputs() {
+    let x = pseudo_random_tensor(&vec![1, 8, 32, 32], 3);
+    let w = pseudo_random_tensor(&vec![4, 8, 3, 3], 5);
+
+    let result = x._conv(
+        &w,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![1; 4],
+        &vec![1, 1],
+        Activation::None,
+    );
+    let direct = x._conv_direct(
+        &w,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![1; 4],
+        &vec![1, 1],
+        Activation::None,
+    );
+
+    assert!(result == direct);
+}
+
+#[test]
+fn test_conv_winograd_matches_direct() {
+    // [input shape, kernel shape, pads]
+    let cases: Vec<(Vec<usize>, Vec<usize>, Vec<usize>)> = vec![
+        (vec![1, 1, 4, 4], vec![1, 1, 3, 3], vec![0, 0, 0, 0]),
+        (vec![2, 3, 7, 6], vec![4, 3, 3, 3], vec![1, 1, 1, 1]),
+        (vec![1, 4, 9, 5], vec![4, 1, 3, 3], vec![1, 1, 1, 1]),
+        (vec![1, 4, 8, 8], vec![6, 2, 3, 3], vec![2, 0, 1, 1]),
+        (vec![1, 2, 3, 3], vec![3, 2, 3, 3], vec![0, 1, 0, 0]),
+    ];
+
+    for (i, (shape, kernel_shape, pads)) in cases.iter().enumerate() {
+        let x = pseudo_random_tensor(shape, 23 + i as u32);
+        let w = pseudo_random_tensor(kernel_shape, 5 + i as u32);
+        let b = pseudo_random_tensor(&vec![kernel_shape[0]], 11 + i as u32);
+        let group = shape[1] / kernel_shape[1];
+        let transformed = w._winograd_kernel();
+
+        for activation in activations() {
+            for bias in vec![None, Some(&b)] {
+                let direct =
+                    x._conv_direct(&w, bias, &vec![1, 1], group, pads, &vec![1, 1], activation);
+                let winograd = x._conv_winograd(&transformed, bias, group, pads, activation);
+
+                assert!(winograd.compare(&direct, 0.0001));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_conv_auto() {
+    let x = pseudo_random_tensor(&vec![1, 2, 6, 6], 1);
+    let w3 = pseudo_random_tensor(&vec![2, 2, 3, 3], 2);
+    let w2 = pseudo_random_tensor(&vec![2, 2, 2, 2], 3);
+    let dilations = vec![1, 1];
+    let pads = vec![1; 4];
+    let strides = vec![1, 1];
+
+    let winograd = x._conv_auto(&w3, None, &dilations, 1, &pads, &strides, Activation::None);
+    let strided = x._conv_auto(
+        &w3,
+        None,
+        &dilations,
+        1,
+        &pads,
+        &vec![2, 2],
+        Activation::None,
+    );
+    let other = x._conv_auto(&w2, None, &dilations, 1, &pads, &strides, Activation::None);
+
+    assert!(winograd == x._conv_winograd(&w3._winograd_kernel(), None, 1, &pads, Activation::None));
+    assert!(
+        strided
+            == x._conv(
+                &w3,
+                None,
+                &dilations,
+                1,
+                &pads,
+                &vec![2, 2],
+                Activation::None
+            )
+    );
+    assert!(other == x._conv(&w2, None, &dilations, 1, &pads, &strides, Activation::None));
+}
+
+#[test]
+fn test_conv_auto_grouped() {
+    let x = pseudo_random_tensor(&vec![1, 4, 5, 6], 13);
+    let w = pseudo_random_tensor(&vec![4, 2, 3, 3], 17);
+    let dilations = vec![1, 1];
+    let pads = vec![1; 4];
+    let strides = vec![1, 1];
+
+    let result = x._conv_auto(&w, None, &dilations, 2, &pads, &strides, Activation::None);
+    let direct = x._conv_direct(&w, None, &dilations, 2, &pads, &strides, Activation::None);
+
+    assert!(result.compare(&direct, 0.0001));
+}
+
+#[test]
+#[should_panic(expected = "Kernel with 2 channels does not match 4 input channels in 1 groups")]
+fn test_conv_winograd_group_mismatch() {
+    let x = pseudo_random_tensor(&vec![1, 4, 4, 4], 19);
+    let w = pseudo_random_tensor(&vec![2, 2, 3, 3], 29);
+
+    x._conv_winograd(
+        &w._winograd_kernel(),
+        None,
+        1,
+        &vec![0; 4],
+        Activation::None,
+    );
+}
+
+#[test]
+fn test_conv_depthwise_matches_direct() {
+    let x = pseudo_random_tensor(&vec![2, 3, 7, 6], 31);
+    let w = pseudo_random_tensor(&vec![3, 1, 3, 2], 37);
+    let b = pseudo_random_tensor(&vec![3], 41);
+
+    for (dilations, pads, strides) in vec![
+        (vec![1, 1], vec![1, 1, 1, 1], vec![1, 1]),
+        (vec![2, 1], vec![0, 2, 1, 0], vec![2, 3]),
+    ] {
+        for activation in activations() {
+            for bias in vec![None, Some(&b)] {
+                let direct = x._conv_direct(&w, bias, &dilations, 3, &pads, &strides, activation);
+                let depthwise =
+                    x._conv_depthwise(&w, bias, &dilations, &pads, &strides, activation);
+
+                assert!(direct == depthwise);
+                assert!(direct == x._conv(&w, bias, &dilations, 3, &pads, &strides, activation));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_conv_pointwise_matches_direct() {
+    // [input shape, kernel shape, pads, strides]
+    let cases: Vec<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>)> = vec![
+        (
+            vec![2, 4, 5, 6],
+            vec![3, 4, 1, 1],
+            vec![0, 0, 0, 0],
+            vec![1, 1],
+        ),
+        (
+            vec![1, 4, 5, 5],
+            vec![6, 2, 1, 1],
+            vec![1, 0, 1, 2],
+            vec![2, 1],
+        ),
+        (vec![1, 3, 7], vec![2, 3, 1], vec![0, 1], vec![3]),
+    ];
+
+    for (i, (shape, kernel_shape, pads, strides)) in cases.iter().enumerate() {
+        let x = pseudo_random_tensor(shape, 43 + i as u32);
+        let w = pseudo_random_tensor(kernel_shape, 47 + i as u32);
+        let b = pseudo_random_tensor(&vec![kernel_shape[0]], 53 + i as u32);
+        let dilations = vec![1; strides.len()];
+        let group = shape[1] / kernel_shape[1];
+
+        for activation in activations() {
+            for bias in vec![None, Some(&b)] {
+                let direct = x._conv_direct(&w, bias, &dilations, group, pads, strides, activation);
+                let pointwise = x._conv_pointwise(&w, bias, pads, strides, activation);
+
+                assert!(direct == pointwise);
+                assert!(direct == x._conv(&w, bias, &dilations, group, pads, strides, activation));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn to_f64(a: &Tensor<f32>) -> Tensor<f64> {
+    Tensor::new_from_shape(
+        a.get_sh(),
+        &a.get_values().iter().map(|&x| x as f64).collect(),
+    )
+}
+
+#[test]
+fn test_conv_backward() {
+    // [input shape, kernel shape, dilations, pads, strides]
+    let cases: Vec<(Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>)> = vec![
+        (
+            vec![2, 2, 5, 4],
+            vec![3, 2, 3, 2],
+            vec![1, 1],
+            vec![1, 0, 1, 1],
+            vec![1, 1],
+        ),
+        (
+            vec![1, 2, 8, 7],
+            vec![2, 2, 3, 3],
+            vec![2, 1],
+            vec![1, 2, 0, 1],
+            vec![2, 3],
+        ),
+        (
+            vec![1, 4, 6, 6],
+            vec![4, 2, 2, 3],
+            vec![1, 1],
+            vec![0, 1, 1, 0],
+            vec![2, 1],
+        ),
+        (vec![1, 3, 5], vec![2, 3, 2], vec![1], vec![3, 2], vec![2]),
+        (
+            vec![1, 2, 3, 4, 3],
+            vec![2, 2, 2, 2, 2],
+            vec![1, 1, 1],
+            vec![0, 1, 0, 1, 0, 1],
+            vec![1, 2, 1],
+        ),
+    ];
+
+    for (i, (shape, kernel_shape, dilations, pads, strides)) in cases.iter().enumerate() {
+        let x = to_f64(&pseudo_random_tensor(shape, 61 + i as u32));
+        let w = to_f64(&pseudo_random_tensor(kernel_shape, 67 + i as u32));
+        let b = to_f64(&pseudo_random_tensor(&vec![kernel_shape[0]], 71 + i as u32));
+        let group = shape[1] / kernel_shape[1];
+
+        let y = x._conv(
+            &w,
+            Some(&b),
+            dilations,
+            group,
+            pads,
+            strides,
+            Activation::None,
+        );
+        let grad = to_f64(&pseudo_random_tensor(y.get_sh(), 73 + i as u32));
+
+        let input_grad = grad._conv_backward_input(&w, shape, dilations, group, pads, strides);
+        let kernel_grad =
+            x._conv_backward_kernel(&grad, kernel_shape, dilations, group, pads, strides);
+        let bias_grad = grad.conv_backward_bias();
+
+        let expected_input = numerical_gradient(&x, &grad, |x| {
+            x._conv(
+                &w,
+                Some(&b),
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::None,
+            )
+        });
+        let expected_kernel = numerical_gradient(&w, &grad, |w| {
+            x._conv(
+                w,
+                Some(&b),
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::None,
+            )
+        });
+        let expected_bias = numerical_gradient(&b, &grad, |b| {
+            x._conv(
+                &w,
+                Some(b),
+                dilations,
+                group,
+                pads,
+                strides,
+                Activation::None,
+            )
+        });
+
+        assert!(input_grad.compare(&expected_input, 0.0001));
+        assert!(kernel_grad.compare(&expected_kernel, 0.0001));
+        assert!(bias_grad.compare(&expected_bias, 0.0001));
+    }
+}
+
+#[test]
+fn test_conv_backward_kernel_grouped() {
+    // [input shape, kernel shape], with 3 groups and a depthwise convolution
+    let cases: Vec<(Vec<usize>, Vec<usize>)> = vec![
+        (vec![2, 6, 5, 4], vec![6, 2, 3, 2]),
+        (vec![1, 3, 4, 4], vec![3, 1, 2, 2]),
+    ];
+    let dilations = vec![1, 1];
+    let pads = vec![1, 0, 0, 1];
+    let strides = vec![1, 2];
+
+    for (i, (shape, kernel_shape)) in cases.iter().enumerate() {
+        let x = to_f64(&pseudo_random_tensor(shape, 79 + i as u32));
+        let w = to_f64(&pseudo_random_tensor(kernel_shape, 83 + i as u32));
+        let group = shape[1] / kernel_shape[1];
+
+        let y = x._conv(
+            &w,
+            None,
+            &dilations,
+            group,
+            &pads,
+            &strides,
+            Activation::None,
+        );
+        let grad = to_f64(&pseudo_random_tensor(y.get_sh(), 89 + i as u32));
+
+        let kernel_grad =
+            x._conv_backward_kernel(&grad, kernel_shape, &dilations, group, &pads, &strides);
+        let expected_kernel = numerical_gradient(&w, &grad, |w| {
+            x._conv(
+                w,
+                None,
+                &dilations,
+                group,
+                &pads,
+                &strides,
+                Activation::None,
+            )
+        });
+
+        assert!(kernel_grad.compare(&expected_kernel, 0.0001));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Kernel with 2 channels does not match 6 input channels in 2 groups")]
+fn test_conv_backward_kernel_group_mismatch() {
+    let x: Tensor<f64> = Tensor::new_from_shape(&vec![1, 6, 3, 3], &vec![1.0; 54]);
+    let grad: Tensor<f64> = Tensor::new_from_shape(&vec![1, 6, 3, 3], &vec![1.0; 54]);
+
+    x._conv_backward_kernel(
+        &grad,
+        &vec![6, 2, 1, 1],
+        &vec![1, 1],
+        2,
+        &vec![0, 0, 0, 0],
+        &vec![1, 1],
+    );
+}
+
+#[test]
+fn test_auto_pads() {
+    let shape = vec![1, 1, 4, 5];
+    let kernel_shape = vec![1, 1, 3, 3];
+    let dilations = vec![1, 1];
+    let strides = vec![2, 1];
+
+    let upper = auto_pads(
+        &shape,
+        &kernel_shape,
+        &dilations,
+        &strides,
+        AutoPad::SameUpper,
+        2,
+    );
+    let lower = auto_pads(
+        &shape,
+        &kernel_shape,
+        &dilations,
+        &strides,
+        AutoPad::SameLower,
+        2,
+    );
+    let valid = auto_pads(
+        &shape,
+        &kernel_shape,
+        &dilations,
+        &strides,
+        AutoPad::Valid,
+        2,
+    );
+
+    assert_eq!(upper, vec![0, 1, 1, 1]);
+    assert_eq!(lower, vec![1, 1, 0, 1]);
+    assert_eq!(valid, vec![0, 0, 0, 0]);
+}
+
+#[test]
+fn test_conv_auto_pad_same_lower() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 5, 5], &(0..25).map(|x| x as f32).collect());
+    let w = Tensor::new_from_shape(&vec![1, 1, 3, 3], &vec![1.; 9]);
+    let dilations = vec![1, 1];
+    let strides = vec![2, 2];
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![12., 27., 24., 63., 108., 81., 72., 117., 84.],
+    );
+
+    let pads = conv_auto_pads(
+        x.get_sh(),
+        w.get_sh(),
+        &dilations,
+        &strides,
+        AutoPad::SameLower,
+    );
+    let result = x._conv(&w, None, &dilations, 1, &pads, &strides, Activation::None);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_average_pool_auto_pad_same_upper() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 5, 5], &(1..26).map(|x| x as f32).collect());
+    let kernel_shape = vec![3, 3];
+    let strides = vec![2, 2];
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![4., 5.5, 7., 11.5, 13., 14.5, 19., 20.5, 22.],
+    );
+
+    let pads = pool_auto_pads(x.get_sh(), &kernel_shape, &strides, AutoPad::SameUpper);
+    let result = x._average_pool(&kernel_shape, &pads, &strides, false);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_conv_transpose_auto_pad_same_upper() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 3, 3], &(0..9).map(|x| x as f32).collect());
+    let w = Tensor::new_from_shape(&vec![2, 1, 3, 3], &vec![1.; 18]);
+    let channel = vec![
+        0., 0., 1., 1., 3., 2., 0., 0., 1., 1., 3., 2., 3., 3., 8., 5., 12., 7., 3., 3., 7., 4.,
+        9., 5., 9., 9., 20., 11., 24., 13., 6., 6., 13., 7., 15., 8.,
+    ];
+    let expected = Tensor::new_from_shape(&vec![1, 2, 6, 6], &[channel.clone(), channel].concat());
+
+    let result = x._conv_transpose_auto_pad(
+        &w,
+        &vec![1, 1],
+        1,
+        &vec![2, 2],
+        &vec![0, 0],
+        None,
+        AutoPad::SameUpper,
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_conv_transpose_output_shape() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 3, 3], &(0..9).map(|x| x as f32).collect());
+    let w = Tensor::new_from_shape(&vec![2, 1, 3, 3], &vec![1.; 18]);
+    let dilations = vec![1, 1];
+    let strides = vec![3, 2];
+
+    let result = x._conv_transpose_auto_pad(
+        &w,
+        &dilations,
+        1,
+        &strides,
+        &vec![0, 0],
+        Some(&vec![10, 8]),
+        AutoPad::NotSet,
+    );
+    let padded = x._conv_transpose_output_padding(
+        &w,
+        &dilations,
+        1,
+        &vec![2, 2, 2, 2],
+        &strides,
+        &vec![1, 1],
+    );
+
+    assert!(result.get_sh() == &vec![1, 2, 10, 8]);
+    assert!(result == padded);
+}
+
+#[test]
+fn test_conv_transpose_output_shape_crops() {
+    let x = pseudo_random_tensor(&vec![1, 1, 3, 3], 83);
+    let w = pseudo_random_tensor(&vec![1, 1, 2, 2], 89);
+    let dilations = vec![1, 1];
+    let strides = vec![1, 1];
+
+    // ONNX pads of [2, 2, 1, 1] exceed the kernel size
+    let result = x._conv_transpose_auto_pad(
+        &w,
+        &dilations,
+        1,
+        &strides,
+        &vec![0, 0],
+        Some(&vec![1, 1]),
+        AutoPad::NotSet,
+    );
+    let full = x._conv_transpose(&w, &dilations, 1, &vec![1, 1, 1, 1], &strides);
+    let expected = full._slice(&vec![2, 2], &vec![3, 3], &vec![2, 3], &vec![1, 1]);
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_conv_transpose_fused() {
+    let x = pseudo_random_tensor(&vec![1, 2, 3, 3], 5);
+    let w = pseudo_random_tensor(&vec![2, 3, 2, 2], 13);
+    let dilations = vec![1, 1];
+    let pads = vec![1, 0, 1, 0];
+    let strides = vec![2, 2];
+
+    let plain = x._conv_transpose(&w, &dilations, 1, &pads, &strides);
+    for activation in activations() {
+        let mut values = plain.get_values().clone();
+        activation.apply(&mut values);
+        let expected = Tensor::new_from_shape(plain.get_sh(), &values);
+
+        let result = x._conv_transpose_fused(&w, &dilations, 1, &pads, &strides, activation);
+
+        assert!(result == expected);
+    }
+}
+
+#[test]
+fn test_pad_modes() {
+    let x = Tensor::new_from_shape(&vec![3], &vec![1., 2., 3.]);
+
+    let constant = Tensor::new_from_shape(&vec![5], &vec![0., 1., 2., 3., 0.]);
+    assert!(x._pad(&vec![1, 1], PadMode::Constant, 0.) == constant);
+
+    let reflect = Tensor::new_from_shape(&vec![7], &vec![3., 2., 1., 2., 3., 2., 1.]);
+    assert!(x._pad(&vec![2, 2], PadMode::Reflect, 0.) == reflect);
+
+    let edge = Tensor::new_from_shape(&vec![6], &vec![1., 1., 2., 3., 3., 3.]);
+    assert!(x._pad(&vec![1, 2], PadMode::Edge, 0.) == edge);
+
+    let wrap = Tensor::new_from_shape(&vec![9], &vec![2., 3., 1., 2., 3., 1., 2., 3., 1.]);
+    assert!(x._pad(&vec![2, 4], PadMode::Wrap, 0.) == wrap);
+}
+
+#[test]
+fn test_pad_reflect_larger_than_dimension() {
+    let x = Tensor::new_from_shape(&vec![3], &vec![1., 2., 3.]);
+    let expected =
+        Tensor::new_from_shape(&vec![11], &vec![1., 2., 3., 2., 1., 2., 3., 2., 1., 2., 3.]);
+
+    assert!(x._pad(&vec![4, 4], PadMode::Reflect, 0.) == expected);
+}
+
+#[test]
+fn test_pad_negative() {
+    let x = Tensor::new_from_shape(&vec![5], &vec![1., 2., 3., 4., 5.]);
+
+    let cropped = Tensor::new_from_shape(&vec![3], &vec![2., 3., 4.]);
+    assert!(x._pad(&vec![-1, -1], PadMode::Constant, 0.) == cropped);
+
+    // The crop happens before the wrap around
+    let wrap = Tensor::new_from_shape(&vec![6], &vec![2., 3., 4., 5., 2., 3.]);
+    assert!(x._pad(&vec![-1, 2], PadMode::Wrap, 0.) == wrap);
+}
+
+#[test]
+#[should_panic]
+fn test_pad_negative_exceeds_axis() {
+    let x = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    x._pad(&vec![-2, -1], PadMode::Constant, 0.);
+}
+
+#[test]
+fn test_pad_axes() {
+    let x: Tensor<i32> = Tensor::new_from_shape(&vec![2, 3], &vec![1, 2, 3, 4, 5, 6]);
+    let expected = Tensor::new_from_shape(&vec![2, 3], &vec![7, 1, 2, 7, 4, 5]);
+
+    let result = x._pad_axes(&vec![1, -1], &vec![1], PadMode::Constant, 7);
+
+    assert!(result == expected);
+}
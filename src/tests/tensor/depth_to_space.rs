@@ -0,0 +1,76 @@
+use crate::tensor::*;
+use crate::utils::DepthToSpaceMode;
+
+#[cfg(test)]
+fn onnx_input() -> Tensor<f32> {
+    let mut values = vec![];
+    for c in 0..8 {
+        for i in 0..6 {
+            values.push((c * 9 + (i / 3) * 3 + i % 3) as f32);
+        }
+    }
+    Tensor::new_from_shape(&vec![1, 8, 2, 3], &values)
+}
+
+#[test]
+fn test_depth_to_space_dcr() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 4, 6],
+        &vec![
+            0., 18., 1., 19., 2., 20., 36., 54., 37., 55., 38., 56., 3., 21., 4., 22., 5., 23.,
+            39., 57., 40., 58., 41., 59., 9., 27., 10., 28., 11., 29., 45., 63., 46., 64., 47.,
+            65., 12., 30., 13., 31., 14., 32., 48., 66., 49., 67., 50., 68.,
+        ],
+    );
+    assert_eq!(onnx_input().depth_to_space(2, 0), expected);
+}
+
+#[test]
+fn test_depth_to_space_crd() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 4, 6],
+        &vec![
+            0., 9., 1., 10., 2., 11., 18., 27., 19., 28., 20., 29., 3., 12., 4., 13., 5., 14., 21.,
+            30., 22., 31., 23., 32., 36., 45., 37., 46., 38., 47., 54., 63., 55., 64., 56., 65.,
+            39., 48., 40., 49., 41., 50., 57., 66., 58., 67., 59., 68.,
+        ],
+    );
+    assert_eq!(onnx_input().depth_to_space(2, 1), expected);
+    assert_eq!(onnx_input().pixel_shuffle(2), expected);
+}
+
+#[test]
+fn test_depth_to_space_matches_reshape_transpose() {
+    let values = (0..2 * 18 * 2 * 3).map(|x| x as f32).collect();
+    let x = Tensor::new_from_shape(&vec![2, 18, 2, 3], &values);
+
+    let expected = x
+        ._reshape(&vec![2, 3, 3, 2, 2, 3])
+        ._transpose(&vec![0, 3, 4, 1, 5, 2])
+        ._reshape(&vec![2, 2, 6, 9]);
+    assert_eq!(x._depth_to_space(3, DepthToSpaceMode::Dcr), expected);
+
+    let expected = x
+        ._reshape(&vec![2, 2, 3, 3, 2, 3])
+        ._transpose(&vec![0, 1, 4, 2, 5, 3])
+        ._reshape(&vec![2, 2, 6, 9]);
+    assert_eq!(x._depth_to_space(3, DepthToSpaceMode::Crd), expected);
+}
+
+#[test]
+fn test_space_to_depth() {
+    let values = (0..2 * 3 * 4 * 6).map(|x| x as i32).collect();
+    let x: Tensor<i32> = Tensor::new_from_shape(&vec![2, 3, 4, 6], &values);
+
+    let expected = x
+        ._reshape(&vec![2, 3, 2, 2, 3, 2])
+        ._transpose(&vec![0, 3, 5, 1, 2, 4])
+        ._reshape(&vec![2, 12, 2, 3]);
+    let result = x.space_to_depth(2);
+    assert_eq!(result, expected);
+    assert_eq!(result.depth_to_space(2, 0), x);
+
+    let result = x.pixel_unshuffle(2);
+    assert_eq!(result.get_sh(), &vec![2, 12, 2, 3]);
+    assert_eq!(result.pixel_shuffle(2), x);
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+use crate::tensor::*;
+
+#[cfg(test)]
+fn weighted_sum(a: &Tensor<f64>, weights: &Tensor<f64>) -> f64 {
+    let mut result = 0.;
+    for i in 0..a.size {
+        result += a.get_ix(i) * weights.get_ix(i);
+    }
+    result
+}
+
+// Central differences of the weighted sum of f with respect to every element of x,
+// the gradient a backward pass computes for an output gradient equal to weights
+#[cfg(test)]
+pub fn numerical_gradient<F>(x: &Tensor<f64>, weights: &Tensor<f64>, f: F) -> Tensor<f64>
+where
+    F: Fn(&Tensor<f64>) -> Tensor<f64>,
+{
+    let h = 0.000001;
+    let mut values = x.get_values().to_vec();
+    let mut result = vec![0.; values.len()];
+    for i in 0..values.len() {
+        let v = values[i];
+        values[i] = v + h;
+        let plus = weighted_sum(&f(&Tensor::new_from_shape(x.get_sh(), &values)), weights);
+        values[i] = v - h;
+        let minus = weighted_sum(&f(&Tensor::new_from_shape(x.get_sh(), &values)), weights);
+        values[i] = v;
+        result[i] = (plus - minus) / (2. * h);
+    }
+    Tensor::new_from_shape(x.get_sh(), &result)
+}
+
+#[cfg(test)]
+pub fn pseudo_random_tensor(shape: &Vec<usize>, seed: u32) -> Tensor<f32> {
+    let mut state = seed;
+    let size: usize = shape.iter().product();
+    let mut values = vec![0.0; size];
+    for i in 0..size {
+        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        values[i] = (state >> 8) as f32 / 8388608.0 - 1.0;
+    }
+    Tensor::new_from_shape(shape, &values)
+}
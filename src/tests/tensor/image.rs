@@ -0,0 +1,153 @@
+#[cfg(test)]
+use crate::tensor::*;
+
+#[cfg(test)]
+const DELTA: f32 = 0.00001;
+
+// RGBA pixels where the red component of (x, y) is 10 * y + x, green is
+// 100 plus red, blue is 200 plus red and alpha is 255
+#[cfg(test)]
+fn rgba(width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            let red = (10 * y + x) as u8;
+            pixels.extend_from_slice(&[red, 100 + red, 200 + red, 255]);
+        }
+    }
+    pixels
+}
+
+#[test]
+fn test_from_rgba_channels() {
+    let pixels = rgba(3, 2);
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        3,
+        2,
+        &vec![0, 1, 2],
+        1.,
+        &vec![],
+        &vec![],
+        0,
+        0,
+        2,
+        3,
+        false,
+    );
+    let expected = Tensor::new_from_shape(
+        &vec![1, 3, 2, 3],
+        &vec![
+            0., 1., 2., 10., 11., 12., 100., 101., 102., 110., 111., 112., 200., 201., 202., 210.,
+            211., 212.,
+        ],
+    );
+    assert!(result.compare(&expected, DELTA));
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        3,
+        2,
+        &vec![2, 1, 0],
+        1.,
+        &vec![],
+        &vec![],
+        0,
+        0,
+        2,
+        3,
+        true,
+    );
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 3, 3],
+        &vec![
+            200., 100., 0., 201., 101., 1., 202., 102., 2., 210., 110., 10., 211., 111., 11., 212.,
+            112., 12.,
+        ],
+    );
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_from_rgba_normalization() {
+    let pixels = rgba(2, 1);
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        2,
+        1,
+        &vec![0, 1],
+        0.5,
+        &vec![1., 2.],
+        &vec![2.],
+        0,
+        0,
+        1,
+        2,
+        false,
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 2, 1, 2], &vec![-0.5, -0.25, 24., 24.25]);
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_from_rgba_center_crop() {
+    let pixels = rgba(4, 4);
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        4,
+        4,
+        &vec![0],
+        1.,
+        &vec![],
+        &vec![],
+        2,
+        2,
+        2,
+        2,
+        false,
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![11., 12., 21., 22.]);
+    assert!(result.compare(&expected, DELTA));
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        4,
+        4,
+        &vec![0],
+        1.,
+        &vec![],
+        &vec![],
+        2,
+        2,
+        1,
+        1,
+        false,
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![16.5]);
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_from_rgba_resize() {
+    let pixels = vec![0, 0, 0, 255, 8, 0, 0, 255];
+
+    let result = Tensor::_from_rgba(
+        &pixels,
+        2,
+        1,
+        &vec![0],
+        1.,
+        &vec![],
+        &vec![],
+        0,
+        0,
+        1,
+        4,
+        false,
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![0., 2., 6., 8.]);
+    assert!(result.compare(&expected, DELTA));
+}
@@ -0,0 +1,133 @@
+use crate::ops::nms::*;
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn boxes(values: Vec<f32>) -> Tensor<f32> {
+    let num_boxes = values.len() / 4;
+    Tensor::new_from_shape(&vec![1, num_boxes, 4], &values)
+}
+
+#[cfg(test)]
+fn scores(values: Vec<f32>) -> Tensor<f32> {
+    let num_boxes = values.len();
+    Tensor::new_from_shape(&vec![1, 1, num_boxes], &values)
+}
+
+#[cfg(test)]
+fn selected(values: Vec<u32>) -> Tensor<u32> {
+    let num_selected = values.len() / 3;
+    Tensor::new_from_shape(&vec![num_selected, 3], &values)
+}
+
+#[cfg(test)]
+fn suppression_boxes() -> Tensor<f32> {
+    boxes(vec![
+        0., 0., 1., 1., 0., 0.1, 1., 1.1, 0., -0.1, 1., 0.9, 0., 10., 1., 11., 0., 10.1, 1., 11.1,
+        0., 100., 1., 101.,
+    ])
+}
+
+#[cfg(test)]
+fn suppression_scores() -> Tensor<f32> {
+    scores(vec![0.9, 0.75, 0.6, 0.95, 0.5, 0.3])
+}
+
+#[test]
+fn test_non_max_suppression() {
+    let result = suppression_boxes().non_max_suppression(&suppression_scores(), 3, 0.5, 0., 0);
+    assert_eq!(result, selected(vec![0, 0, 3, 0, 0, 0, 0, 0, 5]));
+
+    let result = suppression_boxes().non_max_suppression(&suppression_scores(), 3, 0.5, 0.4, 0);
+    assert_eq!(result, selected(vec![0, 0, 3, 0, 0, 0]));
+
+    let result = suppression_boxes().non_max_suppression(&suppression_scores(), 0, 0.5, 0., 0);
+    assert_eq!(result.get_sh(), &vec![0, 3]);
+}
+
+#[test]
+fn test_non_max_suppression_flipped_coordinates() {
+    let x = boxes(vec![
+        1., 1., 0., 0., 0., 0.1, 1., 1.1, 0., 0.9, 1., -0.1, 0., 10., 1., 11., 1., 10.1, 0., 11.1,
+        1., 101., 0., 100.,
+    ]);
+    let result = x.non_max_suppression(&suppression_scores(), 3, 0.5, 0., 0);
+    assert_eq!(result, selected(vec![0, 0, 3, 0, 0, 0, 0, 0, 5]));
+}
+
+#[test]
+fn test_non_max_suppression_center_point_box() {
+    let x = boxes(vec![
+        0.5, 0.5, 1., 1., 0.5, 0.6, 1., 1., 0.5, 0.4, 1., 1., 0.5, 10.5, 1., 1., 0.5, 10.6, 1., 1.,
+        0.5, 100.5, 1., 1.,
+    ]);
+    let result = x._non_max_suppression(&suppression_scores(), 3, 0.5, 0., BoxFormat::Center);
+    assert_eq!(result, selected(vec![0, 0, 3, 0, 0, 0, 0, 0, 5]));
+}
+
+#[test]
+fn test_non_max_suppression_batches_and_classes() {
+    let mut box_values = suppression_boxes().get_values().clone();
+    box_values.extend(suppression_boxes().get_values().iter());
+    let x = Tensor::new_from_shape(&vec![2, 6, 4], &box_values);
+
+    let mut score_values = suppression_scores().get_values().clone();
+    score_values.extend(suppression_scores().get_values().iter());
+    score_values.extend(suppression_scores().get_values().iter());
+    score_values.extend(vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+    let s = Tensor::new_from_shape(&vec![2, 2, 6], &score_values);
+
+    let result = x.non_max_suppression(&s, 2, 0.5, 0., 0);
+    assert_eq!(
+        result,
+        selected(vec![
+            0, 0, 3, 0, 0, 0, 0, 1, 3, 0, 1, 0, 1, 0, 3, 1, 0, 0, 1, 1, 5, 1, 1, 4
+        ])
+    );
+}
+
+#[test]
+fn test_soft_non_max_suppression_linear() {
+    let (indices, result_scores) = suppression_boxes()._soft_non_max_suppression(
+        &suppression_scores(),
+        6,
+        0.5,
+        0.,
+        BoxFormat::Corners,
+        SoftNmsMethod::Linear,
+    );
+    assert_eq!(
+        indices,
+        selected(vec![0, 0, 3, 0, 0, 0, 0, 0, 5, 0, 0, 1, 0, 0, 4, 0, 0, 2])
+    );
+    let expected = Tensor::new_from_shape(
+        &vec![6],
+        &vec![
+            0.95,
+            0.9,
+            0.3,
+            0.75 * 0.2 / 1.1,
+            0.5 * 0.2 / 1.1,
+            0.6 * 0.2 / 1.1 / 3.,
+        ],
+    );
+    assert!(result_scores.compare(&expected, DELTA));
+
+    let (indices, result_scores) =
+        suppression_boxes().soft_non_max_suppression(&suppression_scores(), 6, 0.5, 0.1, 0, 0, 0.);
+    assert_eq!(indices, selected(vec![0, 0, 3, 0, 0, 0, 0, 0, 5, 0, 0, 1]));
+    let expected = Tensor::new_from_shape(&vec![4], &vec![0.95, 0.9, 0.3, 0.75 * 0.2 / 1.1]);
+    assert!(result_scores.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_soft_non_max_suppression_gaussian() {
+    let x = boxes(vec![0., 0., 1., 1., 0., 0.1, 1., 1.1, 0., -0.1, 1., 0.9]);
+    let s = scores(vec![0.9, 0.75, 0.6]);
+
+    let (indices, result_scores) = x.soft_non_max_suppression(&s, 3, 0., 0., 0, 1, 0.5);
+    assert_eq!(indices, selected(vec![0, 0, 0, 0, 0, 1, 0, 0, 2]));
+    let expected = Tensor::new_from_shape(&vec![3], &vec![0.9, 0.19661160, 0.06466356]);
+    assert!(result_scores.compare(&expected, DELTA));
+}
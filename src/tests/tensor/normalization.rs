@@ -0,0 +1,181 @@
+use crate::tensor::*;
+#[cfg(test)]
+use crate::tests::tensor::gradient;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_layer_norm() {
+    let x = Tensor::new_from_shape(&vec![2, 2], &vec![1., 3., 2., 6.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![2., 1.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![-2., 2., -2., 2.]);
+
+    let result = x.layer_norm(1, 0., &scale, &bias);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_instance_norm() {
+    let x = Tensor::new_from_shape(&vec![1, 2, 2], &vec![1., 3., 2., 6.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let expected = Tensor::new_from_shape(&vec![1, 2, 2], &vec![-1., 1., -1., 3.]);
+
+    let result = x.instance_norm(0., &scale, &bias);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_group_norm() {
+    let x = Tensor::new_from_shape(&vec![1, 4, 1], &vec![1., 3., 2., 6.]);
+    let scale = Tensor::new_from_shape(&vec![4], &vec![1., 1., 2., 2.]);
+    let bias = Tensor::new_from_shape(&vec![4], &vec![0., 0., 1., 1.]);
+    let expected = Tensor::new_from_shape(&vec![1, 4, 1], &vec![-1., 1., -1., 3.]);
+
+    let result = x.group_norm(2, 0., &scale, &bias);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+#[should_panic]
+fn test_group_norm_indivisible_groups() {
+    let x = Tensor::new_from_shape(&vec![1, 3, 1], &vec![1., 3., 2.]);
+    let scale = Tensor::new_from_shape(&vec![3], &vec![1., 1., 1.]);
+    let bias = Tensor::new_from_shape(&vec![3], &vec![0., 0., 0.]);
+
+    x.group_norm(2, 0., &scale, &bias);
+}
+
+#[test]
+fn test_lp_normalize() {
+    let x = Tensor::new_from_shape(&vec![2, 2], &vec![3., 4., 0., 0.]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![0.6, 0.8, 0., 0.]);
+    assert!(x.lp_normalize(1, 2).compare(&expected, DELTA));
+
+    let x = Tensor::new_from_shape(&vec![2, 2], &vec![1., 2., -3., 2.]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![0.25, 0.5, -0.75, 0.5]);
+    assert!(x.lp_normalize(0, 1).compare(&expected, DELTA));
+}
+
+#[test]
+fn test_layer_norm_backward() {
+    let x = Tensor::new_from_shape(&vec![2, 3], &vec![1., 3., 2., 6., -1., 0.5]);
+    let scale = Tensor::new_from_shape(&vec![3], &vec![2., 1., 0.5]);
+    let bias = Tensor::new_from_shape(&vec![3], &vec![0., 1., -1.]);
+    let weights = vec![0.3, -1., 2., 0.7, 1.5, -0.2];
+    let grad = Tensor::new_from_shape(&vec![2, 3], &weights);
+
+    let (input_grad, scale_grad, bias_grad) = x.layer_norm_backward(&grad, 1, 0.001, &scale);
+
+    let expected_input =
+        gradient::numerical_gradient(&x, &grad, |x| x.layer_norm(1, 0.001, &scale, &bias));
+    let expected_scale =
+        gradient::numerical_gradient(&scale, &grad, |s| x.layer_norm(1, 0.001, s, &bias));
+    let expected_bias =
+        gradient::numerical_gradient(&bias, &grad, |b| x.layer_norm(1, 0.001, &scale, b));
+    assert!(input_grad.compare(&expected_input, 0.0001));
+    assert!(scale_grad.compare(&expected_scale, 0.0001));
+    assert!(bias_grad.compare(&expected_bias, 0.0001));
+}
+
+#[test]
+fn test_group_norm_backward() {
+    let x = Tensor::new_from_shape(&vec![1, 4, 2], &vec![1., 3., 2., 6., -1., 0.5, 4., -2.]);
+    let scale = Tensor::new_from_shape(&vec![4], &vec![2., 1., 0.5, -1.]);
+    let bias = Tensor::new_from_shape(&vec![4], &vec![0., 1., -1., 2.]);
+    let weights = vec![0.3, -1., 2., 0.7, 1.5, -0.2, 0.1, 1.];
+    let grad = Tensor::new_from_shape(&vec![1, 4, 2], &weights);
+
+    let (input_grad, scale_grad, _) = x.group_norm_backward(&grad, 2, 0.001, &scale);
+
+    let expected_input =
+        gradient::numerical_gradient(&x, &grad, |x| x.group_norm(2, 0.001, &scale, &bias));
+    let expected_scale =
+        gradient::numerical_gradient(&scale, &grad, |s| x.group_norm(2, 0.001, s, &bias));
+    assert!(input_grad.compare(&expected_input, 0.0001));
+    assert!(scale_grad.compare(&expected_scale, 0.0001));
+}
+
+#[test]
+fn test_instance_norm_backward() {
+    let x = Tensor::new_from_shape(&vec![2, 2, 2], &vec![1., 3., 2., 6., -1., 0.5, 4., -2.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![2., -1.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let weights = vec![0.3, -1., 2., 0.7, 1.5, -0.2, 0.1, 1.];
+    let grad = Tensor::new_from_shape(&vec![2, 2, 2], &weights);
+
+    let (input_grad, scale_grad, _) = x.instance_norm_backward(&grad, 0.001, &scale);
+
+    let expected_input =
+        gradient::numerical_gradient(&x, &grad, |x| x.instance_norm(0.001, &scale, &bias));
+    let expected_scale =
+        gradient::numerical_gradient(&scale, &grad, |s| x.instance_norm(0.001, s, &bias));
+    assert!(input_grad.compare(&expected_input, 0.0001));
+    assert!(scale_grad.compare(&expected_scale, 0.0001));
+}
+
+#[test]
+fn test_lp_normalize_backward() {
+    let x = Tensor::new_from_shape(&vec![2, 3], &vec![1., 3., -2., 6., -1., 0.5]);
+    let weights = vec![0.3, -1., 2., 0.7, 1.5, -0.2];
+    let grad = Tensor::new_from_shape(&vec![2, 3], &weights);
+
+    for p in 1..3 {
+        for axis in 0..2 {
+            let result = x.lp_normalize_backward(&grad, axis, p);
+            let expected = gradient::numerical_gradient(&x, &grad, |x| x.lp_normalize(axis, p));
+            assert!(result.compare(&expected, 0.0001));
+        }
+    }
+}
+
+#[test]
+fn test_batch_norm_train() {
+    let x = Tensor::new_from_shape(&vec![2, 2, 2], &vec![1., 3., 1., 3., 5., 7., 1., 3.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let running_mean = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let running_var = Tensor::new_from_shape(&vec![2], &vec![1., 1.]);
+
+    let (output, new_mean, new_var, saved_mean, saved_inv_std) =
+        x.batch_norm_train(&scale, &bias, &running_mean, &running_var, 0.9, 0.);
+
+    let s = (5.0 as f32).sqrt();
+    let expected = Tensor::new_from_shape(
+        &vec![2, 2, 2],
+        &vec![-3. / s, -1. / s, -1., 3., 1. / s, 3. / s, -1., 3.],
+    );
+    assert!(output.compare(&expected, DELTA));
+    assert!(new_mean.compare(&Tensor::new_from_shape(&vec![2], &vec![0.4, 1.1]), DELTA));
+    assert!(new_var.compare(&Tensor::new_from_shape(&vec![2], &vec![1.4, 1.]), DELTA));
+    assert!(saved_mean.compare(&Tensor::new_from_shape(&vec![2], &vec![4., 2.]), DELTA));
+    assert!(saved_inv_std.compare(&Tensor::new_from_shape(&vec![2], &vec![1. / s, 1.]), DELTA));
+}
+
+#[test]
+fn test_batch_norm_backward() {
+    let x = Tensor::new_from_shape(&vec![2, 2, 2], &vec![1., 3., 2., 6., -1., 0.5, 4., -2.]);
+    let scale = Tensor::new_from_shape(&vec![2], &vec![2., -1.]);
+    let bias = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let running = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+    let weights = vec![0.3, -1., 2., 0.7, 1.5, -0.2, 0.1, 1.];
+    let grad = Tensor::new_from_shape(&vec![2, 2, 2], &weights);
+
+    let train = |x: &Tensor<f64>, scale: &Tensor<f64>, bias: &Tensor<f64>| {
+        x.batch_norm_train(scale, bias, &running, &running, 0.9, 0.001)
+    };
+    let (_, _, _, saved_mean, saved_inv_std) = train(&x, &scale, &bias);
+    let (input_grad, scale_grad, bias_grad) =
+        x.batch_norm_backward(&grad, &scale, &saved_mean, &saved_inv_std);
+
+    let expected_input = gradient::numerical_gradient(&x, &grad, |x| train(x, &scale, &bias).0);
+    let expected_scale = gradient::numerical_gradient(&scale, &grad, |s| train(&x, s, &bias).0);
+    let expected_bias = gradient::numerical_gradient(&bias, &grad, |b| train(&x, &scale, b).0);
+    assert!(input_grad.compare(&expected_input, 0.0001));
+    assert!(scale_grad.compare(&expected_scale, 0.0001));
+    assert!(bias_grad.compare(&expected_bias, 0.0001));
+}
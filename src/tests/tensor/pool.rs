@@ -1,5 +1,8 @@
 use crate::assert_delta;
+use crate::ops::accumulate::*;
 use crate::tensor::*;
+#[cfg(test)]
+use crate::tests::tensor::gradient::pseudo_random_tensor;
 use crate::utils::uint32_array;
 
 const DELTA: f32 = 0.00001;
@@ -13,3 +16,232 @@ fn test_tensor_sum() {
 
     assert!(result.compare(&expected, DELTA));
 }
+
+#[test]
+fn test_average_pool_with_mode_accuracy() {
+    let size = 512 * 512;
+    let values = pseudo_random_tensor(&vec![size], 54321)
+        .abs()
+        .get_values()
+        .clone();
+    let reference = values.iter().map(|&x| x as f64).sum::<f64>() / size as f64;
+
+    let a = Tensor::new_from_shape(&vec![1, 1, 512, 512], &values);
+
+    let result = a._average_pool_with_mode(
+        &vec![512, 512],
+        &vec![0; 4],
+        &vec![1; 2],
+        false,
+        SumMode::Kahan,
+    );
+
+    assert!(((result.get_ix(0) as f64 - reference) / reference).abs() < 1e-6);
+}
+
+#[test]
+fn test_average_pool_widened_u8() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![255, 254, 250, 3]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![191]);
+
+    let result = a._average_pool_widened(&vec![2, 2], &vec![0; 4], &vec![1; 2], false);
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_max_pool() {
+    let a = Tensor::new_from_shape(
+        &vec![1, 1, 4, 4],
+        &vec![
+            1., 2., 5., 6., 3., 4., 8., 7., 9., 12., 13., 14., 11., 10., 16., 15.,
+        ],
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![4., 8., 12., 16.]);
+    let expected_indices: Tensor<u32> =
+        Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![5, 6, 9, 14]);
+
+    let (result, indices) =
+        a._max_pool(&vec![2, 2], &vec![0; 4], &vec![2; 2], &vec![1; 2], false, 0);
+
+    assert!(result.compare(&expected, DELTA));
+    assert!(indices == expected_indices);
+}
+
+#[test]
+fn test_max_pool_ceil_mode() {
+    let values: Vec<f32> = (1..17).map(|x| x as f32).collect();
+    let a = Tensor::new_from_shape(&vec![1, 1, 4, 4], &values);
+
+    let (floor, _) = a._max_pool(&vec![3, 3], &vec![0; 4], &vec![2; 2], &vec![1; 2], false, 0);
+    let (ceil, indices) = a._max_pool(&vec![3, 3], &vec![0; 4], &vec![2; 2], &vec![1; 2], true, 0);
+
+    assert!(floor.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![11.]),
+        DELTA
+    ));
+    assert!(ceil.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![11., 12., 15., 16.]),
+        DELTA
+    ));
+    assert!(indices == Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![10, 11, 14, 15]));
+}
+
+#[test]
+fn test_max_pool_dilations_and_pads() {
+    let a = Tensor::new_from_shape(
+        &vec![1, 2, 5],
+        &vec![1., 5., 2., 4., 3., -1., -2., -3., -4., -5.],
+    );
+
+    let (result, indices) = a._max_pool(&vec![2], &vec![0, 0], &vec![1], &vec![2], false, 0);
+    assert!(result.compare(
+        &Tensor::new_from_shape(&vec![1, 2, 3], &vec![2., 5., 3., -1., -2., -3.]),
+        DELTA
+    ));
+    assert!(indices == Tensor::new_from_shape(&vec![1, 2, 3], &vec![2, 1, 4, 5, 6, 7]));
+
+    // Padded positions are never selected, even if all values are negative
+    let (result, _) = a._max_pool(&vec![2], &vec![1, 1], &vec![2], &vec![1], false, 0);
+    assert!(result.compare(
+        &Tensor::new_from_shape(&vec![1, 2, 3], &vec![1., 5., 4., -1., -2., -4.]),
+        DELTA
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Pads of axis 0 in max pool have to be smaller than the kernel size 2")]
+fn test_max_pool_pads_exceed_kernel() {
+    let a = Tensor::new_from_shape(&vec![1, 1, 3], &vec![1., 2., 3.]);
+
+    a._max_pool(&vec![2], &vec![0, 2], &vec![1], &vec![1], false, 0);
+}
+
+#[test]
+fn test_max_pool_storage_order() {
+    let a = Tensor::new_from_shape(&vec![1, 1, 2, 3], &vec![1., 2., 3., 6., 4., 5.]);
+
+    let (_, row_major) = a._max_pool(&vec![2, 3], &vec![0; 4], &vec![1; 2], &vec![1; 2], false, 0);
+    let (_, column_major) =
+        a._max_pool(&vec![2, 3], &vec![0; 4], &vec![1; 2], &vec![1; 2], false, 1);
+
+    assert!(row_major == Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![3]));
+    assert!(column_major == Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![1]));
+}
+
+#[test]
+fn test_global_pools() {
+    let a = Tensor::new_from_shape(&vec![1, 2, 2, 2], &vec![1., 2., 3., 4., -1., -5., 3., -4.]);
+
+    let average = a.global_average_pool();
+    let max = a.global_max_pool();
+
+    assert!(average.compare(
+        &Tensor::new_from_shape(&vec![1, 2, 1, 1], &vec![2.5, -1.75]),
+        DELTA
+    ));
+    assert!(max.compare(
+        &Tensor::new_from_shape(&vec![1, 2, 1, 1], &vec![4., 3.]),
+        DELTA
+    ));
+}
+
+#[test]
+fn test_global_average_pool_widened_u8() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 3], &vec![200, 250, 255]);
+
+    let result = a.global_average_pool_widened();
+
+    assert!(result == Tensor::new_from_shape(&vec![1, 1, 1], &vec![235]));
+}
+
+#[test]
+fn test_lp_pool() {
+    let a = Tensor::new_from_shape(&vec![1, 1, 4], &vec![3., -4., 1., 2.]);
+
+    let result = a._lp_pool(2, &vec![2], &vec![0, 0], &vec![2]);
+    let l1 = a._lp_pool(1, &vec![2], &vec![1, 1], &vec![2]);
+
+    assert!(result.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 2], &vec![5., (5.0 as f32).sqrt()]),
+        DELTA
+    ));
+    assert!(l1.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 3], &vec![3., 5., 2.]),
+        DELTA
+    ));
+}
+
+#[test]
+#[should_panic(expected = "p of lp pool has to be at least 1")]
+fn test_lp_pool_zero_p() {
+    let a = Tensor::new_from_shape(&vec![1, 1, 4], &vec![3., -4., 1., 2.]);
+
+    a._lp_pool(0, &vec![2], &vec![0, 0], &vec![2]);
+}
+
+#[test]
+fn test_global_lp_pool() {
+    let a = Tensor::new_from_shape(&vec![2, 1, 2, 1], &vec![3., -4., 6., 8.]);
+
+    let result = a.global_lp_pool(2);
+
+    assert!(result.compare(
+        &Tensor::new_from_shape(&vec![2, 1, 1, 1], &vec![5., 10.]),
+        DELTA
+    ));
+}
+
+#[test]
+fn test_average_pool_backward() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 3], &vec![1., 2., 3.]);
+
+    let grad = Tensor::new_from_shape(&vec![1, 1, 2], &vec![1., 2.]);
+    let result = x._average_pool_backward(&grad, &vec![2], &vec![0, 0], &vec![1], false);
+    assert!(result.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 3], &vec![0.5, 1.5, 1.]),
+        DELTA
+    ));
+
+    let grad = Tensor::new_from_shape(&vec![1, 1, 3], &vec![1., 2., 4.]);
+    let excluded = x._average_pool_backward(&grad, &vec![2], &vec![1, 0], &vec![1], false);
+    let included = x._average_pool_backward(&grad, &vec![2], &vec![1, 0], &vec![1], true);
+    assert!(excluded.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 3], &vec![2., 3., 2.]),
+        DELTA
+    ));
+    assert!(included.compare(
+        &Tensor::new_from_shape(&vec![1, 1, 3], &vec![1.5, 3., 2.]),
+        DELTA
+    ));
+}
+
+#[test]
+fn test_average_pool_backward_2d() {
+    let x = Tensor::new_from_shape(&vec![1, 2, 2, 3], &vec![0.; 12]);
+    let grad = Tensor::new_from_shape(&vec![1, 2, 1, 2], &vec![4., 8., 2., 6.]);
+
+    let result = x._average_pool_backward(&grad, &vec![2, 2], &vec![0; 4], &vec![1, 1], false);
+
+    assert!(result.compare(
+        &Tensor::new_from_shape(
+            &vec![1, 2, 2, 3],
+            &vec![1., 3., 2., 1., 3., 2., 0.5, 2., 1.5, 0.5, 2., 1.5],
+        ),
+        DELTA
+    ));
+}
+
+#[test]
+fn test_max_pool_backward() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 4], &vec![1., 3., 2., 5.]);
+    let grad = Tensor::new_from_shape(&vec![1, 1, 3], &vec![1., 2., 4.]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 4], &vec![0., 3., 0., 4.]);
+
+    let result = x._max_pool_backward(&grad, &vec![2], &vec![0, 0], &vec![1], &vec![1], false);
+    let (_, indices) = x._max_pool(&vec![2], &vec![0, 0], &vec![1], &vec![1], false, 0);
+    let from_indices = grad._max_pool_backward_indices(&indices, x.get_sh());
+
+    assert!(result.compare(&expected, DELTA));
+    assert!(from_indices.compare(&expected, DELTA));
+}
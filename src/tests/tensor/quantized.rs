@@ -0,0 +1,107 @@
+#[cfg(test)]
+use crate::tensor::*;
+
+#[test]
+fn test_conv_integer_with_padding() {
+    let x: Tensor<u8> =
+        Tensor::new_from_shape(&vec![1, 1, 3, 3], &vec![2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    let w: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1, 1, 1, 1]);
+    let w_zero_point: Tensor<u8> = Tensor::new_from_shape(&vec![1], &vec![0]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 4, 4],
+        &vec![1, 3, 5, 3, 5, 12, 16, 9, 11, 24, 28, 15, 7, 15, 17, 9],
+    );
+
+    let result = x._conv_integer(
+        1,
+        &w,
+        &w_zero_point,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![1, 1, 1, 1],
+        &vec![1, 1],
+    );
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_conv_integer_per_channel() {
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![255, 0, 128, 3]);
+    let w: Tensor<i8> = Tensor::new_from_shape(&vec![2, 1, 1, 1], &vec![-128, 127]);
+    let w_zero_point: Tensor<i8> = Tensor::new_from_shape(&vec![2], &vec![0, -1]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 2, 2],
+        &vec![-32640, 0, -16384, -384, 32640, 0, 16384, 384],
+    );
+
+    let result = x._conv_integer(
+        0,
+        &w,
+        &w_zero_point,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![0; 4],
+        &vec![1, 1],
+    );
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_qlinear_conv_per_channel() {
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![10, 20, 30, 40]);
+    let w: Tensor<i8> = Tensor::new_from_shape(&vec![2, 1, 1, 1], &vec![2, -4]);
+    let w_scale = Tensor::new_from_shape(&vec![2], &vec![0.25, 0.5]);
+    let w_zero_point: Tensor<i8> = Tensor::new_from_shape(&vec![2], &vec![0, 0]);
+    let bias: Tensor<i32> = Tensor::new_from_shape(&vec![2], &vec![4, -2]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 2, 2],
+        &vec![128, 131, 134, 136, 128, 118, 108, 98],
+    );
+
+    let result = x._qlinear_conv(
+        0.5,
+        10,
+        &w,
+        &w_scale,
+        &w_zero_point,
+        1.,
+        128,
+        Some(&bias),
+        &vec![1, 1],
+        1,
+        &vec![0; 4],
+        &vec![1, 1],
+    );
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_qlinear_conv_saturates() {
+    let x: Tensor<i8> = Tensor::new_from_shape(&vec![1, 1, 1, 2], &vec![100, -100]);
+    let w: Tensor<i8> = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![100]);
+    let w_scale = Tensor::new_from_shape(&vec![1], &vec![1.]);
+    let w_zero_point: Tensor<i8> = Tensor::new_from_shape(&vec![1], &vec![0]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 2], &vec![127, -128]);
+
+    let result = x._qlinear_conv(
+        1.,
+        0,
+        &w,
+        &w_scale,
+        &w_zero_point,
+        1.,
+        0,
+        None,
+        &vec![1, 1],
+        1,
+        &vec![0; 4],
+        &vec![1, 1],
+    );
+
+    assert!(result == expected);
+}
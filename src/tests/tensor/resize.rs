@@ -0,0 +1,234 @@
+use crate::ops::resize::*;
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn range_4x4() -> Tensor<f32> {
+    let values = (1..17).map(|x| x as f32).collect();
+    Tensor::new_from_shape(&vec![1, 1, 4, 4], &values)
+}
+
+#[test]
+fn test_resize_nearest_upsample() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 4, 6],
+        &vec![
+            1., 1., 1., 2., 2., 2., 1., 1., 1., 2., 2., 2., 3., 3., 3., 4., 4., 4., 3., 3., 3., 4.,
+            4., 4.,
+        ],
+    );
+
+    let result = x._resize(
+        &vec![],
+        &vec![1., 1., 2., 3.],
+        &vec![],
+        ResizeMode::Nearest(NearestMode::RoundPreferFloor),
+        CoordinateTransform::HalfPixel,
+        0.,
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_resize_nearest_downsample_sizes() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 4], &vec![1., 2., 3., 4., 5., 6., 7., 8.]);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 3], &vec![1., 2., 4.]);
+
+    let result = x._resize(
+        &vec![1, 1, 1, 3],
+        &vec![],
+        &vec![],
+        ResizeMode::Nearest(NearestMode::RoundPreferFloor),
+        CoordinateTransform::HalfPixel,
+        0.,
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_resize_nearest_matches_upsample() {
+    let x = range_4x4();
+    let scales = vec![1., 1., 2., 3.];
+
+    let result = x._resize(
+        &vec![],
+        &scales,
+        &vec![],
+        ResizeMode::Nearest(NearestMode::Floor),
+        CoordinateTransform::Asymmetric,
+        0.,
+    );
+
+    assert!(result == x._upsample(&scales));
+}
+
+#[test]
+fn test_resize_linear_half_pixel() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 4, 4],
+        &vec![
+            1., 1.25, 1.75, 2., 1.5, 1.75, 2.25, 2.5, 2.5, 2.75, 3.25, 3.5, 3., 3.25, 3.75, 4.,
+        ],
+    );
+
+    let result = x._resize(
+        &vec![],
+        &vec![1., 1., 2., 2.],
+        &vec![],
+        ResizeMode::Linear,
+        CoordinateTransform::HalfPixel,
+        0.,
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_resize_linear_align_corners() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 4, 4],
+        &vec![
+            1., 1.3333333, 1.6666667, 2., 1.6666667, 2., 2.3333333, 2.6666667, 2.3333333,
+            2.6666667, 3., 3.3333333, 3., 3.3333333, 3.6666667, 4.,
+        ],
+    );
+
+    let result = x._resize(
+        &vec![],
+        &vec![1., 1., 2., 2.],
+        &vec![],
+        ResizeMode::Linear,
+        CoordinateTransform::AlignCorners,
+        0.,
+    );
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_resize_cubic_downsample() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![
+            1.47119141,
+            2.78125,
+            4.08251953,
+            6.71142578,
+            8.02148438,
+            9.32275391,
+            11.91650391,
+            13.2265625,
+            14.52783203,
+        ],
+    );
+
+    let result = range_4x4()._resize(
+        &vec![],
+        &vec![1., 1., 0.8, 0.8],
+        &vec![],
+        ResizeMode::Cubic(-0.75, false),
+        CoordinateTransform::HalfPixel,
+        0.,
+    );
+
+    assert!(result.compare(&expected, 0.0001));
+}
+
+#[test]
+fn test_resize_cubic_exclude_outside() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![
+            1.36812675,
+            2.6695014,
+            4.0133367,
+            6.57362535,
+            7.875,
+            9.2188353,
+            11.94896657,
+            13.25034122,
+            14.59417652,
+        ],
+    );
+
+    let result = range_4x4()._resize(
+        &vec![],
+        &vec![1., 1., 0.8, 0.8],
+        &vec![],
+        ResizeMode::Cubic(-0.5, true),
+        CoordinateTransform::HalfPixel,
+        0.,
+    );
+
+    assert!(result.compare(&expected, 0.0001));
+}
+
+#[test]
+fn test_resize_tf_crop_and_resize() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![7.6, 7.9, 8.2, 8.8, 9.1, 9.4, 10., 10.3, 10.6],
+    );
+
+    let result = range_4x4()._resize(
+        &vec![1, 1, 3, 3],
+        &vec![],
+        &vec![0., 0., 0.4, 0.6, 1., 1., 0.6, 0.8],
+        ResizeMode::Linear,
+        CoordinateTransform::TfCropAndResize,
+        0.,
+    );
+
+    assert!(result.compare(&expected, 0.0001));
+}
+
+#[test]
+fn test_resize_tf_crop_and_resize_extrapolation() {
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![7.6, 10., 10., 12.4, 10., 10., 10., 10., 10.],
+    );
+
+    let result = range_4x4()._resize(
+        &vec![1, 1, 3, 3],
+        &vec![],
+        &vec![0., 0., 0.4, 0.6, 1., 1., 1.2, 1.7],
+        ResizeMode::Linear,
+        CoordinateTransform::TfCropAndResize,
+        10.,
+    );
+
+    assert!(result.compare(&expected, 0.0001));
+}
+
+#[test]
+fn test_resize_linear_integer() {
+    let x: Tensor<u8> = Tensor::new_from_shape(&vec![1, 3], &vec![0, 3, 255]);
+    let expected = Tensor::new_from_shape(&vec![1, 5], &vec![0, 2, 3, 129, 255]);
+
+    let result = x._resize(
+        &vec![1, 5],
+        &vec![],
+        &vec![],
+        ResizeMode::Linear,
+        CoordinateTransform::AlignCorners,
+        0,
+    );
+
+    assert!(result == expected);
+}
+
+#[test]
+fn test_nearest_mode_index() {
+    assert_eq!(NearestMode::RoundPreferFloor.index(1.5), 1);
+    assert_eq!(NearestMode::RoundPreferCeil.index(1.5), 2);
+    assert_eq!(NearestMode::RoundPreferFloor.index(1.6), 2);
+    assert_eq!(NearestMode::Floor.index(1.9), 1);
+    assert_eq!(NearestMode::Ceil.index(1.1), 2);
+}
@@ -0,0 +1,91 @@
+use crate::ops::roi::*;
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn grid_4x4(batches: usize) -> Tensor<f32> {
+    let mut values = vec![];
+    for n in 0..batches {
+        for i in 0..16 {
+            values.push((n * 100 + i) as f32);
+        }
+    }
+    Tensor::new_from_shape(&vec![batches, 1, 4, 4], &values)
+}
+
+#[test]
+fn test_roi_align_average() {
+    let x = grid_4x4(1);
+    let rois = Tensor::new_from_shape(&vec![1, 4], &vec![0., 0., 3., 3.]);
+    let batch_indices = Tensor::new_from_shape(&vec![1], &vec![0]);
+
+    let result = x._roi_align(
+        &rois,
+        &batch_indices,
+        3,
+        3,
+        2,
+        1.,
+        RoiPoolMode::Average,
+        false,
+    );
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![2.5, 3.5, 4.5, 6.5, 7.5, 8.5, 10.5, 11.5, 12.5],
+    );
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_roi_align_half_pixel() {
+    let x = grid_4x4(1);
+    let rois = Tensor::new_from_shape(&vec![1, 4], &vec![0., 0., 3., 3.]);
+    let batch_indices = Tensor::new_from_shape(&vec![1], &vec![0]);
+
+    let result = x._roi_align(
+        &rois,
+        &batch_indices,
+        3,
+        3,
+        2,
+        1.,
+        RoiPoolMode::Average,
+        true,
+    );
+    let expected = Tensor::new_from_shape(
+        &vec![1, 1, 3, 3],
+        &vec![0.625, 1.5, 2.5, 4.125, 5., 6., 8.125, 9., 10.],
+    );
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_roi_align_max_batch_indices() {
+    let x = grid_4x4(2);
+    let rois = Tensor::new_from_shape(&vec![2, 4], &vec![0., 0., 4., 4., 0., 0., 4., 4.]);
+    let batch_indices = Tensor::new_from_shape(&vec![2], &vec![0, 1]);
+
+    let result = x.roi_align(&rois, &batch_indices, 2, 2, 1, 1., 1, false);
+    let expected = Tensor::new_from_shape(
+        &vec![2, 1, 2, 2],
+        &vec![5., 7., 13., 15., 105., 107., 113., 115.],
+    );
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_max_roi_pool() {
+    let x = grid_4x4(1);
+
+    let rois = Tensor::new_from_shape(&vec![2, 5], &vec![0., 0., 0., 3., 3., 0., 1., 1., 2., 2.]);
+    let result = x._max_roi_pool(&rois, 2, 2, 1.);
+    let expected =
+        Tensor::new_from_shape(&vec![2, 1, 2, 2], &vec![5., 7., 13., 15., 5., 6., 9., 10.]);
+    assert!(result.compare(&expected, DELTA));
+
+    let rois = Tensor::new_from_shape(&vec![1, 5], &vec![0., 2., 2., 6., 6.]);
+    let result = x._max_roi_pool(&rois, 2, 2, 0.5);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![10., 11., 14., 15.]);
+    assert!(result.compare(&expected, DELTA));
+}
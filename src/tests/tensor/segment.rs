@@ -0,0 +1,95 @@
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_segment_sum() {
+    let a = Tensor::new_from_shape(&vec![4, 2], &vec![1., 2., 3., 4., 5., 6., 7., 8.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![4], &vec![0, 0, 1, 3]);
+    let expected = Tensor::new_from_shape(&vec![4, 2], &vec![4., 6., 5., 6., 0., 0., 7., 8.]);
+
+    let result = a.segment_sum(&ids);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_segment_mean() {
+    let a = Tensor::new_from_shape(&vec![3, 2], &vec![1., 2., 3., 6., 5., 6.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![3], &vec![0, 0, 1]);
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![2., 4., 5., 6.]);
+
+    let result = a.segment_mean(&ids);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_segment_max_min() {
+    let a = Tensor::new_from_shape(&vec![3, 2], &vec![1., -2., 3., -6., -5., 6.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![3], &vec![0, 0, 1]);
+    let expected_max = Tensor::new_from_shape(&vec![2, 2], &vec![3., -2., -5., 6.]);
+    let expected_min = Tensor::new_from_shape(&vec![2, 2], &vec![1., -6., -5., 6.]);
+
+    assert!(a.segment_max(&ids).compare(&expected_max, DELTA));
+    assert!(a.segment_min(&ids).compare(&expected_min, DELTA));
+}
+
+#[test]
+#[should_panic]
+fn test_segment_sum_unsorted_ids() {
+    let a = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![2], &vec![1, 0]);
+
+    a.segment_sum(&ids);
+}
+
+#[test]
+fn test_unsorted_segment_sum() {
+    let a = Tensor::new_from_shape(&vec![4, 2], &vec![1., 2., 3., 4., 5., 6., 7., 8.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![4], &vec![2, 0, 2, 3]);
+    let expected = Tensor::new_from_shape(&vec![4, 2], &vec![3., 4., 0., 0., 6., 8., 7., 8.]);
+
+    let result = a.unsorted_segment_sum(&ids, 4);
+
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_unsorted_segment_max_rank_3() {
+    let a: Tensor<i32> = Tensor::new_from_shape(
+        &vec![3, 2, 2],
+        &vec![1, 8, 3, 4, 5, 6, 7, -8, -1, -2, -3, -4],
+    );
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![3], &vec![1, 0, 1]);
+    let expected = Tensor::new_from_shape(&vec![2, 2, 2], &vec![5, 6, 7, -8, 1, 8, 3, 4]);
+
+    let result = a.unsorted_segment_max(&ids, 2);
+
+    assert!(result == expected);
+}
+
+#[test]
+#[should_panic(expected = "Segment id 5 out of range for 3 segments")]
+fn test_unsorted_segment_sum_id_out_of_range() {
+    let a = Tensor::new_from_shape(&vec![2], &vec![1., 2.]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![2], &vec![0, 5]);
+
+    a.unsorted_segment_sum(&ids, 3);
+}
+
+#[test]
+fn test_segment_sum_widened() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![3, 2], &vec![200, 1, 100, 2, 255, 3]);
+    let ids: Tensor<u32> = Tensor::new_from_shape(&vec![3], &vec![0, 0, 1]);
+
+    let sum = Tensor::new_from_shape(&vec![2, 2], &vec![255, 3, 255, 3]);
+    assert!(a.segment_sum_widened(&ids) == sum);
+
+    let mean = Tensor::new_from_shape(&vec![2, 2], &vec![150, 2, 255, 3]);
+    assert!(a.segment_mean_widened(&ids) == mean);
+
+    let b: Tensor<i8> = Tensor::new_from_shape(&vec![3], &vec![-100, -100, 127]);
+    let mean = Tensor::new_from_shape(&vec![2], &vec![-100, 127]);
+    assert!(b.unsorted_segment_mean_widened(&ids, 2) == mean);
+}
@@ -0,0 +1,128 @@
+use crate::ops::spatial::*;
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[cfg(test)]
+fn identity_theta() -> Tensor<f32> {
+    Tensor::new_from_shape(&vec![1, 2, 3], &vec![1., 0., 0., 0., 1., 0.])
+}
+
+#[cfg(test)]
+fn grid(points: Vec<f32>) -> Tensor<f32> {
+    let num_points = points.len() / 2;
+    Tensor::new_from_shape(&vec![1, 1, num_points, 2], &points)
+}
+
+#[test]
+fn test_affine_grid() {
+    let result = identity_theta()._affine_grid(&vec![1, 1, 2, 3], false);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 3, 2],
+        &vec![
+            -2. / 3.,
+            -0.5,
+            0.,
+            -0.5,
+            2. / 3.,
+            -0.5,
+            -2. / 3.,
+            0.5,
+            0.,
+            0.5,
+            2. / 3.,
+            0.5,
+        ],
+    );
+    assert!(result.compare(&expected, DELTA));
+
+    let theta = Tensor::new_from_shape(&vec![1, 2, 3], &vec![0., -1., 0.5, 1., 0., 0.]);
+    let result = theta._affine_grid(&vec![1, 1, 2, 2], true);
+    let expected = Tensor::new_from_shape(
+        &vec![1, 2, 2, 2],
+        &vec![1.5, -1., 1.5, 1., -0.5, -1., -0.5, 1.],
+    );
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_grid_sample_identity() {
+    let values = (0..2 * 3 * 4 * 5).map(|x| x as f32).collect();
+    let x = Tensor::new_from_shape(&vec![2, 3, 4, 5], &values);
+    let theta = Tensor::new_from_shape(
+        &vec![2, 2, 3],
+        &vec![1., 0., 0., 0., 1., 0., 1., 0., 0., 0., 1., 0.],
+    );
+
+    for &align_corners in [false, true].iter() {
+        let g = theta._affine_grid(&vec![2, 3, 4, 5], align_corners);
+        for mode in 0..3 {
+            let result = x.grid_sample(&g, mode, 0, align_corners);
+            assert!(result.compare(&x, 0.0001));
+        }
+    }
+}
+
+#[test]
+fn test_grid_sample_padding_modes() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    let g = grid(vec![0., 0., -1., -1., 1., 1., 2., 0.]);
+
+    let result = x._grid_sample(&g, GridSampleMode::Bilinear, GridPaddingMode::Zeros, true);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![2.5, 1., 4., 1.5]);
+    assert!(result.compare(&expected, DELTA));
+
+    let result = x._grid_sample(&g, GridSampleMode::Bilinear, GridPaddingMode::Border, true);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![2.5, 1., 4., 3.]);
+    assert!(result.compare(&expected, DELTA));
+
+    let result = x._grid_sample(
+        &g,
+        GridSampleMode::Bilinear,
+        GridPaddingMode::Reflection,
+        true,
+    );
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![2.5, 1., 4., 2.5]);
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_grid_sample_nearest() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    let g = grid(vec![-0.6, -0.6, 0.6, -0.6, -1.5, 0.6, 0., 0.]);
+
+    // Without align_corners the pixel centers are at -0.5 and 0.5, 0 rounds to even
+    let result = x.grid_sample(&g, 1, 0, false);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![1., 2., 0., 1.]);
+    assert!(result.compare(&expected, DELTA));
+
+    let result = x.grid_sample(&g, 1, 1, false);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![1., 2., 3., 1.]);
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_grid_sample_bicubic() {
+    let values = (0..4 * 5).map(|x| (x % 5) as f32).collect();
+    let x = Tensor::new_from_shape(&vec![1, 1, 4, 5], &values);
+    let g = grid(vec![0., -0.2, -0.25, 0.3, 0.25, 0.9, -0.45, -0.7]);
+
+    // Exact at pixels and halfway between them, x = 1.1 has the weights
+    // of the cubic coefficient -0.75 at ratio 0.1
+    let result = x.grid_sample(&g, 2, 1, true);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 4], &vec![2., 1.5, 2.5, 1.136]);
+    assert!(result.compare(&expected, DELTA));
+}
+
+#[test]
+fn test_grid_sample_pads_both_coordinates() {
+    let x = Tensor::new_from_shape(&vec![1, 1, 2, 2], &vec![1., 2., 3., 4.]);
+    // x = -0.25 lies inside the sampling border, y = 2.5 below it
+    let g = grid(vec![-0.75, 2.]);
+
+    // Both coordinates are clamped to the border pixels, so x = 0 exactly and
+    // the bicubic taps left of it do not contribute
+    let result = x.grid_sample(&g, 2, 1, false);
+    let expected = Tensor::new_from_shape(&vec![1, 1, 1, 1], &vec![3.]);
+    assert!(result.compare(&expected, DELTA));
+}
@@ -0,0 +1,115 @@
+use crate::tensor::*;
+
+const DELTA: f32 = 0.00001;
+
+#[test]
+fn test_unique_sorted() {
+    let a = Tensor::new_from_shape(&vec![2, 3], &vec![2., 1., 1., 3., 4., 3.]);
+
+    let (values, indices, inverse, counts) = a._unique(None, true);
+
+    assert!(values.compare(
+        &Tensor::new_from_shape(&vec![4], &vec![1., 2., 3., 4.]),
+        DELTA
+    ));
+    assert!(indices == Tensor::new_from_shape(&vec![4], &vec![1, 0, 3, 4]));
+    assert!(inverse == Tensor::new_from_shape(&vec![6], &vec![1, 0, 0, 2, 3, 2]));
+    assert!(counts == Tensor::new_from_shape(&vec![4], &vec![2, 1, 2, 1]));
+}
+
+#[test]
+fn test_unique_not_sorted() {
+    let a = Tensor::new_from_shape(&vec![6], &vec![2., 1., 1., 3., 4., 3.]);
+
+    let (values, indices, inverse, counts) = a._unique(None, false);
+
+    assert!(values.compare(
+        &Tensor::new_from_shape(&vec![4], &vec![2., 1., 3., 4.]),
+        DELTA
+    ));
+    assert!(indices == Tensor::new_from_shape(&vec![4], &vec![0, 1, 3, 4]));
+    assert!(inverse == Tensor::new_from_shape(&vec![6], &vec![0, 1, 1, 2, 3, 2]));
+    assert!(counts == Tensor::new_from_shape(&vec![4], &vec![1, 2, 2, 1]));
+}
+
+#[test]
+fn test_unique_axis() {
+    // Example from the ONNX Unique specification
+    let a = Tensor::new_from_shape(&vec![1, 4, 2], &vec![1., 1., 0., 1., 2., 1., 0., 1.]);
+
+    let (values, indices, inverse, counts) = a._unique(Some(1), true);
+
+    let expected = Tensor::new_from_shape(&vec![1, 3, 2], &vec![0., 1., 1., 1., 2., 1.]);
+    assert!(values.compare(&expected, DELTA));
+    assert!(indices == Tensor::new_from_shape(&vec![3], &vec![1, 0, 2]));
+    assert!(inverse == Tensor::new_from_shape(&vec![4], &vec![1, 0, 2, 0]));
+    assert!(counts == Tensor::new_from_shape(&vec![3], &vec![2, 1, 1]));
+}
+
+#[test]
+fn test_unique_axis_last() {
+    let a = Tensor::new_from_shape(&vec![2, 3], &vec![1., 2., 1., 3., 4., 3.]);
+
+    let (values, _, inverse, counts) = a._unique(Some(1), false);
+
+    let expected = Tensor::new_from_shape(&vec![2, 2], &vec![1., 2., 3., 4.]);
+    assert!(values.compare(&expected, DELTA));
+    assert!(inverse == Tensor::new_from_shape(&vec![3], &vec![0, 1, 0]));
+    assert!(counts == Tensor::new_from_shape(&vec![2], &vec![2, 1]));
+}
+
+#[test]
+fn test_bincount() {
+    let a: Tensor<u8> = Tensor::new_from_shape(&vec![5], &vec![0, 1, 1, 3, 1]);
+
+    let counts = a._bincount::<u32>(None, 0);
+    let padded = a._bincount::<u32>(None, 6);
+
+    assert!(counts == Tensor::new_from_shape(&vec![4], &vec![1, 3, 0, 1]));
+    assert!(padded == Tensor::new_from_shape(&vec![6], &vec![1, 3, 0, 1, 0, 0]));
+}
+
+#[test]
+fn test_bincount_weighted() {
+    let a: Tensor<i32> = Tensor::new_from_shape(&vec![4], &vec![0, 2, 2, 0]);
+    let weights = Tensor::new_from_shape(&vec![4], &vec![0.5, 1., 2., 0.25]);
+    let expected = Tensor::new_from_shape(&vec![3], &vec![0.75, 0., 3.]);
+
+    assert!(a._bincount(Some(&weights), 0).compare(&expected, DELTA));
+}
+
+#[test]
+#[should_panic]
+fn test_bincount_negative() {
+    let a: Tensor<i32> = Tensor::new_from_shape(&vec![2], &vec![0, -1]);
+
+    a._bincount::<u32>(None, 0);
+}
+
+#[test]
+#[should_panic(expected = "one weight per value")]
+fn test_bincount_weights_size() {
+    let a: Tensor<i32> = Tensor::new_from_shape(&vec![3], &vec![0, 1, 1]);
+    let weights = Tensor::new_from_shape(&vec![2], &vec![0.5, 1.]);
+
+    a._bincount(Some(&weights), 0);
+}
+
+#[test]
+fn test_histogram() {
+    let a = Tensor::new_from_shape(&vec![6], &vec![0., 0.5, 1., 1.5, 2., 3.]);
+
+    let result = a._histogram(3, None);
+    let with_range = a._histogram(2, Some((0.5, 1.5)));
+
+    assert!(result == Tensor::new_from_shape(&vec![3], &vec![2, 2, 2]));
+    assert!(with_range == Tensor::new_from_shape(&vec![2], &vec![1, 2]));
+}
+
+#[test]
+#[should_panic(expected = "at least one bin")]
+fn test_histogram_zero_bins() {
+    let a = Tensor::new_from_shape(&vec![2], &vec![0., 1.]);
+
+    a._histogram(0, None);
+}
@@ -1,4 +1,5 @@
 use js_sys::Uint32Array;
+use num_traits::Float;
 
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -20,6 +21,17 @@ macro_rules! assert_delta {
     };
 }
 
+// Rounds to the nearest integer, ties go to the even integer as in ONNX QuantizeLinear
+pub fn round_half_to_even<T: Float>(x: T) -> T {
+    let rounded = x.round();
+    let two = T::one() + T::one();
+    if (x - x.trunc()).abs() * two == T::one() && rounded % two != T::zero() {
+        rounded - x.signum()
+    } else {
+        rounded
+    }
+}
+
 pub fn uint32_array(vec: &Vec<usize>) -> Uint32Array {
     let result = Uint32Array::new_with_length(vec.len() as u32);
     for i in 0..vec.len() {
@@ -28,6 +40,14 @@ pub fn uint32_array(vec: &Vec<usize>) -> Uint32Array {
     result
 }
 
+pub fn usize_vec(array: &Uint32Array) -> Vec<usize> {
+    let mut result = vec![0; array.length() as usize];
+    for i in 0..array.length() {
+        result[i as usize] = array.get_index(i) as usize;
+    }
+    result
+}
+
 pub fn conv_output_size(
     in_sizes: &Vec<usize>,
     kernels: &Vec<usize>,
@@ -45,6 +65,251 @@ pub fn conv_output_size(
     result
 }
 
+// Output size of pooling without kernel offset. With ceil_mode the last window
+// may run over the padded input, but it has to start inside the input or the begin padding
+pub fn pool_output_size(
+    in_sizes: &Vec<usize>,
+    kernels: &Vec<usize>,
+    pads: &Vec<usize>,
+    dilations: &Vec<usize>,
+    strides: &Vec<usize>,
+    ceil_mode: bool,
+) -> Vec<usize> {
+    let mut result = vec![0; dilations.len()];
+    for i in 0..dilations.len() {
+        let dkernel = dilations[i] * (kernels[i] - 1) + 1;
+        let padded = in_sizes[i + 2] + pads[i] + pads[i + dilations.len()];
+        if padded < dkernel {
+            panic!("Pooling kernel is larger than the padded input");
+        }
+
+        if ceil_mode {
+            result[i] = (padded - dkernel + strides[i] - 1) / strides[i] + 1;
+            if (result[i] - 1) * strides[i] >= in_sizes[i + 2] + pads[i] {
+                result[i] -= 1;
+            }
+        } else {
+            result[i] = (padded - dkernel) / strides[i] + 1;
+        }
+    }
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PadMode {
+    Constant,
+    Reflect,
+    Edge,
+    Wrap,
+}
+
+impl PadMode {
+    // Mode: 0 == constant, 1 == reflect, 2 == edge, 3 == wrap
+    pub fn from_i32(mode: i32) -> PadMode {
+        match mode {
+            0 => PadMode::Constant,
+            1 => PadMode::Reflect,
+            2 => PadMode::Edge,
+            3 => PadMode::Wrap,
+            _ => panic!("Unknown pad mode {}", mode),
+        }
+    }
+
+    // Index into an axis of the given size for the position x, which may lie
+    // outside of the axis. None means the constant value is used
+    pub fn source_index(&self, x: isize, size: usize) -> Option<usize> {
+        let size = size as isize;
+        if x >= 0 && x < size {
+            return Some(x as usize);
+        }
+        match *self {
+            PadMode::Constant => None,
+            PadMode::Edge => Some(x.max(0).min(size - 1) as usize),
+            PadMode::Wrap => Some(x.rem_euclid(size) as usize),
+            PadMode::Reflect => {
+                if size == 1 {
+                    return Some(0);
+                }
+                let period = 2 * (size - 1);
+                let x = x.rem_euclid(period);
+                if x < size {
+                    Some(x as usize)
+                } else {
+                    Some((period - x) as usize)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AutoPad {
+    NotSet,
+    SameUpper,
+    SameLower,
+    Valid,
+}
+
+impl AutoPad {
+    // Mode: 0 == NOTSET, 1 == SAME_UPPER, 2 == SAME_LOWER, 3 == VALID
+    pub fn from_u32(mode: u32) -> AutoPad {
+        match mode {
+            0 => AutoPad::NotSet,
+            1 => AutoPad::SameUpper,
+            2 => AutoPad::SameLower,
+            3 => AutoPad::Valid,
+            _ => panic!("Unknown auto pad mode {}", mode),
+        }
+    }
+}
+
+// Order of the channels of a block in DepthToSpace and SpaceToDepth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthToSpaceMode {
+    // depth, column, row: block position is the outer part of the channel
+    Dcr,
+    // column, row, depth: block position is the inner part of the channel, as in pixel shuffle
+    Crd,
+}
+
+impl DepthToSpaceMode {
+    // Mode: 0 == DCR, 1 == CRD
+    pub fn from_u32(mode: u32) -> DepthToSpaceMode {
+        match mode {
+            0 => DepthToSpaceMode::Dcr,
+            1 => DepthToSpaceMode::Crd,
+            _ => panic!("Unknown depth to space mode {}", mode),
+        }
+    }
+
+    // Channel of the blocked tensor holding channel c at block position (block_h, block_w)
+    pub fn blocked_channel(
+        &self,
+        c: usize,
+        block_h: usize,
+        block_w: usize,
+        channels: usize,
+        blocksize: usize,
+    ) -> usize {
+        match *self {
+            DepthToSpaceMode::Dcr => (block_h * blocksize + block_w) * channels + c,
+            DepthToSpaceMode::Crd => (c * blocksize + block_h) * blocksize + block_w,
+        }
+    }
+}
+
+// Pads in the layout [begins..., ends...] for which convolution and pooling
+// produce an output of size ceil(input / stride) with the SAME modes, or no
+// padding with VALID. For SAME_UPPER the extra padding goes to the end
+pub fn auto_pads(
+    in_sizes: &Vec<usize>,
+    kernels: &Vec<usize>,
+    dilations: &Vec<usize>,
+    strides: &Vec<usize>,
+    auto_pad: AutoPad,
+    kernel_offset: usize,
+) -> Vec<usize> {
+    let data_rank = dilations.len();
+    let mut result = vec![0; 2 * data_rank];
+    if auto_pad == AutoPad::Valid {
+        return result;
+    }
+    if auto_pad == AutoPad::NotSet {
+        panic!("Auto pad mode NOTSET requires explicit pads");
+    }
+
+    for i in 0..data_rank {
+        let in_size = in_sizes[i + 2];
+        let dkernel = dilations[i] * (kernels[i + kernel_offset] - 1) + 1;
+        let out_size = (in_size + strides[i] - 1) / strides[i];
+        let needed = (out_size - 1) * strides[i] + dkernel;
+        let total = if needed > in_size {
+            needed - in_size
+        } else {
+            0
+        };
+
+        let small = total / 2;
+        if auto_pad == AutoPad::SameUpper {
+            result[i] = small;
+            result[i + data_rank] = total - small;
+        } else {
+            result[i] = total - small;
+            result[i + data_rank] = small;
+        }
+    }
+    result
+}
+
+// Pads of a convolution of an input of shape in_sizes with a kernel of shape kernels
+pub fn conv_auto_pads(
+    in_sizes: &Vec<usize>,
+    kernels: &Vec<usize>,
+    dilations: &Vec<usize>,
+    strides: &Vec<usize>,
+    auto_pad: AutoPad,
+) -> Vec<usize> {
+    auto_pads(in_sizes, kernels, dilations, strides, auto_pad, 2)
+}
+
+// Pads of a pooling of an input of shape in_sizes with the given kernel shape
+pub fn pool_auto_pads(
+    in_sizes: &Vec<usize>,
+    kernel_shape: &Vec<usize>,
+    strides: &Vec<usize>,
+    auto_pad: AutoPad,
+) -> Vec<usize> {
+    auto_pads(
+        in_sizes,
+        kernel_shape,
+        &vec![1; strides.len()],
+        strides,
+        auto_pad,
+        0,
+    )
+}
+
+// Pads of ConvTranspose as defined by ONNX, which can be negative. Without an
+// output shape it is input * stride for the SAME modes, and the pads are 0 otherwise
+pub fn conv_transpose_pads(
+    in_sizes: &Vec<usize>,
+    kernels: &Vec<usize>,
+    dilations: &Vec<usize>,
+    strides: &Vec<usize>,
+    output_padding: &Vec<usize>,
+    output_shape: Option<&Vec<usize>>,
+    auto_pad: AutoPad,
+    kernel_offset: usize,
+) -> Vec<i64> {
+    let data_rank = dilations.len();
+    let mut result = vec![0; 2 * data_rank];
+
+    for i in 0..data_rank {
+        let in_size = in_sizes[i + 2] as i64;
+        let dkernel = (dilations[i] * (kernels[i + kernel_offset] - 1) + 1) as i64;
+        let stride = strides[i] as i64;
+
+        let out_size = match output_shape {
+            Some(shape) => shape[i] as i64,
+            None => match auto_pad {
+                AutoPad::SameUpper | AutoPad::SameLower => in_size * stride,
+                _ => continue,
+            },
+        };
+
+        let total = stride * (in_size - 1) + output_padding[i] as i64 + dkernel - out_size;
+        let small = total.div_euclid(2);
+        if auto_pad == AutoPad::SameUpper {
+            result[i] = small;
+            result[i + data_rank] = total - small;
+        } else {
+            result[i] = total - small;
+            result[i + data_rank] = small;
+        }
+    }
+    result
+}
+
 pub fn conv_transpose_output_size(
     in_sizes: &Vec<usize>,
     kernels: &Vec<usize>,